@@ -55,6 +55,9 @@ defmodule Wasmex do
   When the WASM code executes the `add_ints` imported function, the execution context is forwarded to
   the given function reference.
   The first param is always the call context (a Map containing e.g. the instances memory).
+  The context contains the instances `memory` if the module exports one named `memory`
+  and a `caller` which gives access to other exported memories by name, see `Wasmex.Memory.from_caller/2`,
+  and to exported globals, see `Wasmex.Caller`.
   All other params are regular parameters as specified by the parameter type list.
 
   Valid parameter/return types are:
@@ -63,12 +66,37 @@ defmodule Wasmex do
   - `:i64` a 64 bit integer
   - `:f32` a 32 bit float
   - `:f64` a 64 bit float
+  - `:v128` a 128 bit SIMD vector, given as a 16 byte binary in little-endian byte order
+
+  Elixir floats can not be NaN or infinite. These float values are given and returned
+  as the atoms `:nan`, `:infinity`, and `:neg_infinity` instead.
 
   The return type must always be one value. (There are preparations to enable WASM to return multiple
   values from a function call. We prepared the API for this future by specifying an array of return types.)
+
+  Calls of functions without results return `{:ok, []}` (`void_as: :empty_list`, the default).
+  Give `void_as: :ok` to return just `:ok` instead:
+
+  ```elixir
+  {:ok, instance } = Wasmex.start_link(%{bytes: bytes, imports: %{}, void_as: :ok})
+  :ok = Wasmex.call_function(instance, "void", [])
+  ```
+
+  The `void_as` option only applies to calls through this GenServer.
+  `Wasmex.Instance.call_exported_function/4` ignores it and always sends `{:ok, []}`.
   """
-  def start_link(%{bytes: bytes, imports: imports}) when is_binary(bytes) do
-    GenServer.start_link(__MODULE__, %{bytes: bytes, imports: stringify_keys(imports)})
+  def start_link(%{bytes: bytes, imports: imports} = opts) when is_binary(bytes) do
+    case Map.get(opts, :void_as, :empty_list) do
+      void_as when void_as in [:empty_list, :ok] ->
+        GenServer.start_link(__MODULE__, %{
+          bytes: bytes,
+          imports: stringify_keys(imports),
+          void_as: void_as
+        })
+
+      void_as ->
+        {:error, "`void_as` must be `:empty_list` or `:ok`, got: #{inspect(void_as)}"}
+    end
   end
 
   def start_link(bytes) when is_binary(bytes) do
@@ -82,6 +110,21 @@ defmodule Wasmex do
     GenServer.call(pid, {:exported_function_exists, stringify(name)})
   end
 
+  @doc """
+  Returns whether the WebAssembly instance can still be used, see `Wasmex.Instance.healthy?/1`.
+  """
+  def healthy?(pid) do
+    GenServer.call(pid, :healthy?)
+  end
+
+  @doc """
+  Returns a map of all exported functions of the WebAssembly instance with their
+  param and result types, see `Wasmex.Instance.exported_functions/1`.
+  """
+  def exported_functions(pid) do
+    GenServer.call(pid, :exported_functions)
+  end
+
   @doc """
   Calls a function with the given `name` and `params` on
   the WebAssembly instance and returns its results.
@@ -90,6 +133,48 @@ defmodule Wasmex do
     GenServer.call(pid, {:call_function, stringify(name), params})
   end
 
+  @doc """
+  Returns a list of all exported functions with their index and signature,
+  see `Wasmex.Instance.list_functions/1`.
+  """
+  def list_functions(pid) do
+    GenServer.call(pid, :list_functions)
+  end
+
+  @doc """
+  Calls the function at `index` (as returned by `list_functions/1`) with the given `params`
+  and returns its results.
+  """
+  def call_function_by_index(pid, index, params) do
+    GenServer.call(pid, {:call_function_by_index, index, params})
+  end
+
+  @doc """
+  Passes `binary` to the function `name` through the instances memory and returns its result
+  as a binary, see `Wasmex.Instance.call_with_memory/5` for the expected function signature and `opts`.
+  """
+  def call_function_with_memory(pid, name, binary, opts \\ []) do
+    GenServer.call(pid, {:call_function_with_memory, stringify(name), binary, opts})
+  end
+
+  @doc """
+  Looks up the exported function with the given `name` once and returns a `Wasmex.Function`
+  handle which can be called repeatedly with `call_resolved_function/3`.
+  """
+  def resolve_function(pid, name) do
+    GenServer.call(pid, {:resolve_function, stringify(name)})
+  end
+
+  @doc """
+  Calls a function handle returned by `resolve_function/2` with the given `params`
+  and returns its results.
+
+  The handle must have been resolved by the same `pid`, otherwise an error is returned.
+  """
+  def call_resolved_function(pid, %Wasmex.Function{} = function, params) do
+    GenServer.call(pid, {:call_resolved_function, function, params})
+  end
+
   @doc """
   Finds the exported memory of the given WASM instance and returns it as a `Wasmex.Memory`.
 
@@ -99,11 +184,25 @@ defmodule Wasmex do
   * uint8 / int8 - (un-)signed 8-bit integer values
   * uint16 / int16 - (un-)signed 16-bit integer values
   * uint32 / int32 - (un-)signed 32-bit integer values
+  * uint64 / int64 - (un-)signed 64-bit integer values
+  * float32 / float64 - 32-bit and 64-bit floating point values
 
   We can think of it as a list of values of the above type (where each value may be larger than a byte).
   The `offset` value can be used to start reading the memory from a chosen position.
   """
-  def memory(pid, type, offset) when type in [:uint8, :int8, :uint16, :int16, :uint32, :int32] do
+  def memory(pid, type, offset)
+      when type in [
+             :uint8,
+             :int8,
+             :uint16,
+             :int16,
+             :uint32,
+             :int32,
+             :uint64,
+             :int64,
+             :float32,
+             :float64
+           ] do
     GenServer.call(pid, {:memory, type, offset})
   end
 
@@ -130,14 +229,26 @@ defmodule Wasmex do
                    }
   """
   @impl true
-  def init(%{bytes: bytes, imports: imports}) when is_binary(bytes) do
+  def init(%{bytes: bytes, imports: imports, void_as: void_as})
+      when is_binary(bytes) and void_as in [:empty_list, :ok] do
     {:ok, instance} = Wasmex.Instance.from_bytes(bytes, imports)
-    {:ok, %{instance: instance, imports: imports}}
+    {:ok, %{instance: instance, imports: imports, void_as: void_as}}
   end
 
   @impl true
   def handle_call({:memory, size, offset}, _from, %{instance: instance} = state)
-      when size in [:uint8, :int8, :uint16, :int16, :uint32, :int32] do
+      when size in [
+             :uint8,
+             :int8,
+             :uint16,
+             :int16,
+             :uint32,
+             :int32,
+             :uint64,
+             :int64,
+             :float32,
+             :float64
+           ] do
     case Wasmex.Memory.from_instance(instance, size, offset) do
       {:ok, memory} -> {:reply, {:ok, memory}, state}
       {:error, error} -> {:reply, {:error, error}, state}
@@ -150,12 +261,68 @@ defmodule Wasmex do
     {:reply, Wasmex.Instance.function_export_exists(instance, name), state}
   end
 
+  @impl true
+  def handle_call(:healthy?, _from, %{instance: instance} = state) do
+    {:reply, Wasmex.Instance.healthy?(instance), state}
+  end
+
+  @impl true
+  def handle_call(:exported_functions, _from, %{instance: instance} = state) do
+    {:reply, Wasmex.Instance.exported_functions(instance), state}
+  end
+
   @impl true
   def handle_call({:call_function, name, params}, from, %{instance: instance} = state) do
     :ok = Wasmex.Instance.call_exported_function(instance, name, params, from)
     {:noreply, state}
   end
 
+  @impl true
+  def handle_call(:list_functions, _from, %{instance: instance} = state) do
+    {:reply, Wasmex.Instance.list_functions(instance), state}
+  end
+
+  @impl true
+  def handle_call({:call_function_by_index, index, params}, from, %{instance: instance} = state) do
+    :ok = Wasmex.Instance.call_function_by_index(instance, index, params, from)
+    {:noreply, state}
+  end
+
+  @impl true
+  def handle_call(
+        {:call_function_with_memory, name, binary, opts},
+        from,
+        %{instance: instance} = state
+      ) do
+    :ok = Wasmex.Instance.call_with_memory(instance, name, binary, from, opts)
+    {:noreply, state}
+  end
+
+  @impl true
+  def handle_call({:resolve_function, name}, _from, %{instance: instance} = state) do
+    {:reply, Wasmex.Instance.resolve_function(instance, name), state}
+  end
+
+  @impl true
+  def handle_call(
+        {:call_resolved_function, function, params},
+        from,
+        %{instance: instance} = state
+      ) do
+    if function.instance_reference == instance.reference do
+      :ok = Wasmex.Function.call(function, params, from)
+      {:noreply, state}
+    else
+      {:reply, {:error, "the function was resolved from another instance"}, state}
+    end
+  end
+
+  @impl true
+  def handle_info({:returned_function_call, {:ok, []}, from}, %{void_as: :ok} = state) do
+    GenServer.reply(from, :ok)
+    {:noreply, state}
+  end
+
   @impl true
   def handle_info({:returned_function_call, result, from}, state) do
     GenServer.reply(from, result)
@@ -168,11 +335,10 @@ defmodule Wasmex do
         %{imports: imports} = state
       ) do
     context =
-      Map.put(
-        context,
-        :memory,
-        Wasmex.Memory.wrap_resource(Map.get(context, :memory), :uint8, 0)
-      )
+      case Map.fetch(context, :memory) do
+        {:ok, memory} -> Map.put(context, :memory, Wasmex.Memory.wrap_resource(memory, :uint8, 0))
+        :error -> context
+      end
 
     {success, return_value} =
       try do
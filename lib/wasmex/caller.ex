@@ -0,0 +1,44 @@
+defmodule Wasmex.Caller do
+  @moduledoc """
+  Gives imported function callbacks access to the exports of the calling instance.
+
+  The `caller` is given in the context of imported function callbacks:
+
+  ```elixir
+  imports = %{
+    env: %{
+      set_error_code: {:fn, [], [], fn context ->
+        {:ok, 42} = Wasmex.Caller.get_global(context.caller, "answer")
+        :ok = Wasmex.Caller.set_global(context.caller, "error_code", 7)
+        nil
+      end}
+    }
+  }
+  ```
+
+  Exported memories can be read with `Wasmex.Memory.from_caller/2`.
+  """
+
+  @doc """
+  Reads the value of the exported global `name`.
+
+  Only globals of type `:i32`, `:i64`, `:f32`, and `:f64` are supported.
+  """
+  @spec get_global(reference(), binary()) ::
+          {:ok, number() | :nan | :infinity | :neg_infinity}
+          | {:error, binary() | :caller_poisoned}
+  def get_global(caller, name) when is_binary(name) do
+    Wasmex.Native.caller_get_global(caller, name)
+  end
+
+  @doc """
+  Sets the exported global `name` to `value`.
+
+  Returns an error if the global is immutable or `value` does not fit the globals type.
+  """
+  @spec set_global(reference(), binary(), number() | :nan | :infinity | :neg_infinity) ::
+          :ok | {:error, binary() | :caller_poisoned}
+  def set_global(caller, name, value) when is_binary(name) do
+    Wasmex.Native.caller_set_global(caller, name, value)
+  end
+end
@@ -0,0 +1,58 @@
+defmodule Wasmex.Function do
+  @moduledoc """
+  A handle to an exported function of a `Wasmex.Instance`.
+
+  Resolving a function once with `Wasmex.Instance.resolve_function/2` and calling the handle
+  avoids looking up the function by name on every call, which helps in tight loops:
+
+  ```elixir
+  {:ok, function} = Wasmex.Instance.resolve_function(instance, "sum")
+  :ok = Wasmex.Function.call(function, [50, -8], from)
+  ```
+  """
+
+  @type t :: %__MODULE__{
+          resource: binary(),
+          reference: reference(),
+          instance_reference: reference()
+        }
+
+  defstruct resource: nil,
+            # The actual NIF function resource.
+            # Normally the compiler will happily do stuff like inlining the
+            # resource in attributes. This will convert the resource into an
+            # empty binary with no warning. This will make that harder to
+            # accidentally do.
+            # It also serves as a handy way to tell file handles apart.
+            reference: nil,
+            # The reference of the `Wasmex.Instance` the function was resolved from.
+            instance_reference: nil
+
+  def wrap_resource(resource, %Wasmex.Instance{reference: instance_reference}) do
+    %__MODULE__{
+      resource: resource,
+      reference: make_ref(),
+      instance_reference: instance_reference
+    }
+  end
+
+  @doc """
+  Calls the function with the given `params`.
+
+  Works like `Wasmex.Instance.call_exported_function/4`: the function is executed in a new
+  OS thread and the calling process receives a `{:returned_function_call, result, from}`
+  message once the execution finished.
+  """
+  @spec call(__MODULE__.t(), [any()], GenServer.from()) :: any()
+  def call(%__MODULE__{resource: resource}, params, from) do
+    Wasmex.Native.instance_call_resolved_function(resource, params, from)
+  end
+end
+
+defimpl Inspect, for: Wasmex.Function do
+  import Inspect.Algebra
+
+  def inspect(dict, opts) do
+    concat(["#Wasmex.Function<", to_doc(dict.reference, opts), ">"])
+  end
+end
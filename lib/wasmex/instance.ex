@@ -15,7 +15,12 @@ defmodule Wasmex.Instance do
 
   All exported functions are accessible via `call_exported_function`.
   Arguments of these functions are automatically casted to WebAssembly values.
-  Note that WebAssembly only knows number datatypes (floats and integers of various sizes).
+  Note that WebAssembly only knows number datatypes (floats and integers of various sizes)
+  and reference types.
+
+  Any Elixir term can be given for an `externref` param; the guest treats it as opaque and
+  the original term is returned when the guest hands it back. `nil` is passed as a null reference.
+  `funcref` values are returned as opaque handles which can be passed back into the guest.
 
   You can pass arbitrary data to WebAssembly, though, by writing this data into its memory. The `memory` function returns a `Memory` struct representing the memory of that particular instance, e.g.:
 
@@ -49,6 +54,20 @@ defmodule Wasmex.Instance do
     end
   end
 
+  @doc """
+  Same as `from_bytes/2` but reads the WebAssembly module (binary or WAT text format) from the file at `path`.
+
+  The file is read and compiled natively, so large modules are not copied into an Elixir binary first.
+  """
+  @spec from_file(binary(), %{optional(binary()) => (... -> any())}) ::
+          {:error, binary()} | {:ok, __MODULE__.t()}
+  def from_file(path, imports) when is_binary(path) and is_map(imports) do
+    case Wasmex.Native.instance_new_from_file(path, imports) do
+      {:ok, resource} -> {:ok, wrap_resource(resource)}
+      {:error, err} -> {:error, err}
+    end
+  end
+
   defp wrap_resource(resource) do
     %__MODULE__{
       resource: resource,
@@ -56,11 +75,61 @@ defmodule Wasmex.Instance do
     }
   end
 
+  @doc """
+  Returns whether a function export with the given `name` exists in the WebAssembly `instance`.
+
+  Functions of an instance which is not `healthy?/1` can not be called,
+  so `false` is returned for them.
+  """
   @spec function_export_exists(__MODULE__.t(), binary()) :: boolean()
   def function_export_exists(%__MODULE__{resource: resource}, name) when is_binary(name) do
     Wasmex.Native.instance_function_export_exists(resource, name)
   end
 
+  @doc """
+  Returns whether the `instance` can still be used.
+
+  A crash in native code while the instance was in use leaves it in an unknown state.
+  All further calls on such an instance return `{:error, :instance_poisoned}` instead of crashing again.
+  """
+  @spec healthy?(__MODULE__.t()) :: boolean()
+  def healthy?(%__MODULE__{resource: resource}) do
+    Wasmex.Native.instance_is_healthy(resource)
+  end
+
+  @doc """
+  Returns a map of all exported functions with their signatures.
+
+  Each function name maps to a tuple of its param types and result types,
+  using the same type atoms as imported functions (`:i32`, `:i64`, `:f32`, `:f64`, `:v128`)
+  plus `:externref` and `:funcref`.
+
+  ```elixir
+  %{"sum" => {[:i32, :i32], [:i32]}} = Wasmex.Instance.exported_functions(instance)
+  ```
+  """
+  @spec exported_functions(__MODULE__.t()) ::
+          %{binary() => {[atom()], [atom()]}} | {:error, :instance_poisoned}
+  def exported_functions(%__MODULE__{resource: resource}) do
+    Wasmex.Native.instance_exported_functions(resource)
+  end
+
+  @doc """
+  Returns a list of all exported functions with their index and signature.
+
+  The index is stable for the lifetime of the `instance` and can be given to
+  `call_function_by_index/4` instead of the functions name.
+
+  ```elixir
+  [{"sum", 0, {[:i32, :i32], [:i32]}} | _] = Wasmex.Instance.list_functions(instance)
+  ```
+  """
+  @spec list_functions(__MODULE__.t()) ::
+          [{binary(), non_neg_integer(), {[atom()], [atom()]}}] | {:error, :instance_poisoned}
+  def list_functions(%__MODULE__{resource: resource}) do
+    Wasmex.Native.instance_list_functions(resource)
+  end
+
   @doc """
   Calls a function with the given `name` and `params` on the WebAssembly `instance`.
   This function assumes to be called within a GenServer context, it expects a `from` argument
@@ -81,10 +150,118 @@ defmodule Wasmex.Instance do
     Wasmex.Native.instance_call_exported_function(resource, name, params, from)
   end
 
+  @doc """
+  Works like `call_exported_function/4`, but finds the function by its index
+  as returned by `list_functions/1`.
+  """
+  @spec call_function_by_index(__MODULE__.t(), non_neg_integer(), [any()], GenServer.from()) ::
+          any()
+  def call_function_by_index(%__MODULE__{resource: resource}, index, params, from)
+      when is_integer(index) and index >= 0 do
+    Wasmex.Native.instance_call_function_by_index(resource, index, params, from)
+  end
+
+  @doc """
+  Looks up the exported function with the given `name` once and returns a `Wasmex.Function` handle.
+
+  Calling the handle with `Wasmex.Function.call/3` skips the lookup by name,
+  which `call_exported_function/4` does on every call.
+  """
+  @spec resolve_function(__MODULE__.t(), binary()) ::
+          {:error, binary() | :instance_poisoned} | {:ok, Wasmex.Function.t()}
+  def resolve_function(%__MODULE__{resource: resource} = instance, name) when is_binary(name) do
+    case Wasmex.Native.instance_resolve_function(resource, name) do
+      {:ok, resource} -> {:ok, Wasmex.Function.wrap_resource(resource, instance)}
+      {:error, err} -> {:error, err}
+    end
+  end
+
+  @doc """
+  Calls the exported function `name` which takes a `(ptr, len)` pair and returns one,
+  the common way for C modules to pass strings.
+
+  `binary` is written into memory allocated with the modules allocator,
+  the function is called with its location, and the returned memory region is read back into a binary.
+  Just like `call_exported_function/4`, the calling process receives a
+  `{:returned_function_call, result, from}` message with `result` being `{:ok, binary}` or `{:error, reason}`.
+
+  Options:
+
+  * `:alloc` - the exported allocator, called like `alloc(len)` or `cabi_realloc(0, 0, 1, len)`.
+    Defaults to `"cabi_realloc"`.
+  * `:dealloc` - an exported `dealloc(ptr, len)` function used to free both memory regions
+    after the call, if the module exports it. Defaults to `"dealloc"`.
+  """
+  @spec call_with_memory(__MODULE__.t(), binary(), binary(), GenServer.from(), keyword()) :: :ok
+  def call_with_memory(%__MODULE__{resource: resource}, name, binary, from, opts \\ [])
+      when is_binary(name) and is_binary(binary) do
+    alloc = Keyword.get(opts, :alloc, "cabi_realloc")
+    dealloc = Keyword.get(opts, :dealloc, "dealloc")
+    Wasmex.Native.instance_call_with_memory(resource, name, binary, from, alloc, dealloc)
+  end
+
+  @doc """
+  Returns the number of elements of the exported table `name`.
+  """
+  @spec table_size(__MODULE__.t(), binary()) ::
+          {:error, binary() | :instance_poisoned} | {:ok, non_neg_integer()}
+  def table_size(%__MODULE__{resource: resource}, name) when is_binary(name) do
+    Wasmex.Native.instance_table_size(resource, name)
+  end
+
+  @doc """
+  Reads the element at `index` of the exported table `name`.
+
+  Elements are returned like `externref` and `funcref` function results:
+  null references as `nil`, `externref` elements as the original Elixir term,
+  and `funcref` elements as opaque handles.
+  """
+  @spec table_get(__MODULE__.t(), binary(), non_neg_integer()) ::
+          {:error, binary() | :instance_poisoned} | {:ok, any()}
+  def table_get(%__MODULE__{resource: resource}, name, index)
+      when is_binary(name) and is_integer(index) and index >= 0 do
+    Wasmex.Native.instance_table_get(resource, name, index)
+  end
+
+  @doc """
+  Sets the element at `index` of the exported table `name`.
+
+  `value` is given like an `externref` or `funcref` function param, `nil` being a null reference.
+  """
+  @spec table_set(__MODULE__.t(), binary(), non_neg_integer(), any()) ::
+          :ok | {:error, binary() | :instance_poisoned}
+  def table_set(%__MODULE__{resource: resource}, name, index, value)
+      when is_binary(name) and is_integer(index) and index >= 0 do
+    Wasmex.Native.instance_table_set(resource, name, index, value)
+  end
+
+  @doc """
+  Grows the exported table `name` by `delta` elements, all set to `init`.
+
+  Returns the previous size of the table.
+  """
+  @spec table_grow(__MODULE__.t(), binary(), non_neg_integer(), any()) ::
+          {:error, binary() | :instance_poisoned} | {:ok, non_neg_integer()}
+  def table_grow(%__MODULE__{resource: resource}, name, delta, init)
+      when is_binary(name) and is_integer(delta) and delta >= 0 do
+    Wasmex.Native.instance_table_grow(resource, name, delta, init)
+  end
+
   @spec memory(__MODULE__.t(), atom(), pos_integer()) ::
-          {:error, binary()} | {:ok, Wasmex.Memory.t()}
+          {:error, binary() | :instance_poisoned} | {:ok, Wasmex.Memory.t()}
   def memory(%__MODULE__{} = instance, size, offset)
-      when size in [:uint8, :int8, :uint16, :int16, :uint32, :int32] do
+      when size in [
+             :uint8,
+             :int8,
+             :uint16,
+             :int16,
+             :uint32,
+             :int32,
+             :uint64,
+             :int64,
+             :float32,
+             :float64
+           ] do
     Wasmex.Memory.from_instance(instance, size, offset)
   end
 end
@@ -28,7 +28,7 @@ defmodule Wasmex.Memory do
   ```
 
   The `Memory` struct views the WebAssembly memory of an instance as an array of values of different types.
-  Possible types are: `uint8`, `int8`, `uint16`, `int16`, `uint32`, and `int32`.
+  Possible types are: `uint8`, `int8`, `uint16`, `int16`, `uint32`, `int32`, `uint64`, `int64`, `float32`, and `float64`.
   The underlying data is not changed when viewed in different types - its just its representation that changes.
 
   | View memory buffer as a sequence of… | Bytes per element |
@@ -39,6 +39,10 @@ defmodule Wasmex.Memory do
   | `uint16` | 2 |
   | `int32`  | 4 |
   | `uint32` | 4 |
+  | `int64`  | 8 |
+  | `uint64` | 8 |
+  | `float32` | 4 |
+  | `float64` | 8 |
   """
 
   @type t :: %__MODULE__{
@@ -57,21 +61,60 @@ defmodule Wasmex.Memory do
             size: nil,
             offset: nil
 
-  @spec from_instance(Wasmex.Instance.t()) :: {:error, binary()} | {:ok, t}
+  @spec from_instance(Wasmex.Instance.t()) :: {:error, binary() | :instance_poisoned} | {:ok, t}
   def from_instance(%Wasmex.Instance{} = instance) do
     from_instance(instance, :uint8, 0)
   end
 
   @spec from_instance(Wasmex.Instance.t(), atom(), non_neg_integer()) ::
-          {:error, binary()} | {:ok, t}
+          {:error, binary() | :instance_poisoned} | {:ok, t}
   def from_instance(%Wasmex.Instance{resource: resource}, size, offset)
-      when size in [:uint8, :int8, :uint16, :int16, :uint32, :int32] do
+      when size in [
+             :uint8,
+             :int8,
+             :uint16,
+             :int16,
+             :uint32,
+             :int32,
+             :uint64,
+             :int64,
+             :float32,
+             :float64
+           ] do
     case Wasmex.Native.memory_from_instance(resource) do
       {:ok, resource} -> {:ok, wrap_resource(resource, size, offset)}
       {:error, err} -> {:error, err}
     end
   end
 
+  @doc """
+  Returns the memory exported under the given `name` by the instance which called an imported function.
+
+  The `caller` is given in the context of imported function callbacks.
+  This gives access to memories not exported as `memory`, which is the only one
+  available as `context.memory`.
+
+  ```elixir
+  fn context ->
+    {:ok, memory} = Wasmex.Memory.from_caller(context.caller, "linear_memory")
+    Wasmex.Memory.get(memory, 0)
+  end
+  ```
+  """
+  @spec from_caller(reference(), binary()) :: {:error, binary() | :caller_poisoned} | {:ok, t}
+  def from_caller(caller, name) do
+    from_caller(caller, name, :uint8, 0)
+  end
+
+  @spec from_caller(reference(), binary(), atom(), non_neg_integer()) ::
+          {:error, binary() | :caller_poisoned} | {:ok, t}
+  def from_caller(caller, name, size, offset) when is_binary(name) do
+    case Wasmex.Native.memory_from_caller(caller, name) do
+      {:ok, resource} -> {:ok, wrap_resource(resource, size, offset)}
+      {:error, err} -> {:error, err}
+    end
+  end
+
   def wrap_resource(resource, size, offset) do
     %__MODULE__{
       resource: resource,
@@ -94,6 +137,10 @@ defmodule Wasmex.Memory do
   | `uint16` | 2 |
   | `int32`  | 4 |
   | `uint32` | 4 |
+  | `int64`  | 8 |
+  | `uint64` | 8 |
+  | `float32` | 4 |
+  | `float64` | 8 |
 
   ```elixir
   {:ok, memory} = Wasmex.Instance.memory(instance, :uint16, 0)
@@ -154,22 +201,51 @@ defmodule Wasmex.Memory do
     Wasmex.Native.memory_grow(resource, pages)
   end
 
-  @spec get(t, non_neg_integer()) :: number()
+  @doc """
+  Copies the whole memory into a snapshot which can later be written back with `restore/2`.
+
+  Restoring a snapshot is usually faster than creating a new instance of a module with an expensive initialization.
+  The snapshot is kept in native memory and returned as an opaque reference.
+  """
+  @spec snapshot(t) :: reference()
+  def snapshot(%__MODULE__{resource: resource}) do
+    Wasmex.Native.memory_snapshot(resource)
+  end
+
+  @doc """
+  Writes a snapshot taken with `snapshot/1` back into memory.
+
+  Memory which was grown after taking the snapshot can not shrink again, all bytes beyond the snapshot are set to zero instead.
+  The memory is grown when it is smaller than the snapshot.
+  """
+  @spec restore(t, reference()) :: :ok
+  def restore(%__MODULE__{resource: resource}, snapshot) do
+    Wasmex.Native.memory_restore(resource, snapshot)
+  end
+
+  @spec get(t, non_neg_integer()) :: number() | :nan | :infinity | :neg_infinity
   def get(%__MODULE__{} = memory, index) do
     get(memory, memory.size, memory.offset, index)
   end
 
-  @spec get(t, atom(), non_neg_integer(), non_neg_integer()) :: number()
+  @spec get(t, atom(), non_neg_integer(), non_neg_integer()) ::
+          number() | :nan | :infinity | :neg_infinity
   def get(%__MODULE__{resource: resource}, size, offset, index) do
     Wasmex.Native.memory_get(resource, size, offset, index)
   end
 
-  @spec set(t, non_neg_integer(), number()) :: number()
+  @spec set(t, non_neg_integer(), number() | :nan | :infinity | :neg_infinity) :: :ok
   def set(%__MODULE__{} = memory, index, value) do
     set(memory, memory.size, memory.offset, index, value)
   end
 
-  @spec set(t, atom(), non_neg_integer(), non_neg_integer(), number()) :: number()
+  @spec set(
+          t,
+          atom(),
+          non_neg_integer(),
+          non_neg_integer(),
+          number() | :nan | :infinity | :neg_infinity
+        ) :: :ok
   def set(%__MODULE__{resource: resource}, size, offset, index, value) do
     Wasmex.Native.memory_set(resource, size, offset, index, value)
   end
@@ -203,6 +279,83 @@ defmodule Wasmex.Memory do
     Wasmex.Native.memory_read_binary(resource, size, offset, index, length)
   end
 
+  @doc """
+  Streams `length` bytes starting at `index` to the process `pid` in chunks of at most `chunk_size` bytes.
+
+  Reading happens in a separate OS thread, so that very large regions never need to be
+  copied into a single binary. Messages are tagged with the `reference` of the returned `Wasmex.MemoryStream`.
+  The receiving process gets one `{:memory_chunk, reference, chunk_index, binary}` message per chunk
+  (`chunk_index` counting up from 0), followed by a final `{:memory_done, reference}` message.
+  If the memory becomes unusable while streaming, a `{:memory_error, reference, :memory_poisoned}`
+  message ends the stream instead.
+
+  The next chunk is only read once the receiver acknowledged the previous one with `ack_chunk/1`,
+  so that no more than one chunk waits in its mailbox. A receiver which stops reading early should
+  call `cancel_stream/1`. Otherwise the stream is cancelled once the stream handle is garbage collected,
+  e.g. when the receiver exits. So when streaming to another process, hand the stream to that process.
+
+  Chunks are not a consistent snapshot of the memory: a running WebAssembly function may write to
+  memory in between two chunks.
+
+  ```elixir
+  {:ok, stream} = Wasmex.Memory.stream_read(memory, 0, 16_777_216, 1_048_576, self())
+  reference = stream.reference
+
+  receive do
+    {:memory_chunk, ^reference, 0, chunk} ->
+      :ok = Wasmex.Memory.ack_chunk(stream)
+      chunk
+  end
+  ```
+  """
+  @spec stream_read(t, non_neg_integer(), non_neg_integer(), pos_integer(), pid()) ::
+          {:ok, Wasmex.MemoryStream.t()}
+  def stream_read(%__MODULE__{} = memory, index, length, chunk_size, pid) do
+    stream_read(memory, memory.size, memory.offset, index, length, chunk_size, pid)
+  end
+
+  @spec stream_read(
+          t,
+          atom(),
+          non_neg_integer(),
+          non_neg_integer(),
+          non_neg_integer(),
+          pos_integer(),
+          pid()
+        ) :: {:ok, Wasmex.MemoryStream.t()}
+  def stream_read(%__MODULE__{resource: resource}, size, offset, index, length, chunk_size, pid) do
+    reference = make_ref()
+
+    {:ok, stream_resource} =
+      Wasmex.Native.memory_stream_read(
+        resource,
+        size,
+        offset,
+        index,
+        length,
+        chunk_size,
+        {pid, reference}
+      )
+
+    {:ok, Wasmex.MemoryStream.wrap_resource(stream_resource, reference)}
+  end
+
+  @doc """
+  Acknowledges the last chunk received from a `stream_read/5` stream, so that the next chunk is sent.
+  """
+  @spec ack_chunk(Wasmex.MemoryStream.t()) :: :ok
+  def ack_chunk(%Wasmex.MemoryStream{resource: resource}) do
+    Wasmex.Native.memory_stream_ack(resource)
+  end
+
+  @doc """
+  Stops a `stream_read/5` stream. No further chunks and no `:memory_done` message are sent.
+  """
+  @spec cancel_stream(Wasmex.MemoryStream.t()) :: :ok
+  def cancel_stream(%Wasmex.MemoryStream{resource: resource}) do
+    Wasmex.Native.memory_stream_cancel(resource)
+  end
+
   @spec read_string(t, non_neg_integer(), non_neg_integer()) :: String.t()
   def read_string(memory, index, length) do
     read_binary(memory, index, length)
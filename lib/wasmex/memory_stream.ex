@@ -0,0 +1,38 @@
+defmodule Wasmex.MemoryStream do
+  @moduledoc """
+  A handle to a memory region being streamed to a process by `Wasmex.Memory.stream_read/5`.
+
+  Chunks are sent as messages tagged with the streams `reference`
+  and acknowledged with `Wasmex.Memory.ack_chunk/1`.
+  The stream is cancelled once the handle is garbage collected.
+  """
+
+  @type t :: %__MODULE__{
+          resource: binary(),
+          reference: reference()
+        }
+
+  defstruct resource: nil,
+            # The actual NIF stream resource.
+            # Normally the compiler will happily do stuff like inlining the
+            # resource in attributes. This will convert the resource into an
+            # empty binary with no warning. This will make that harder to
+            # accidentally do.
+            # It also tags all messages of the stream.
+            reference: nil
+
+  def wrap_resource(resource, reference) do
+    %__MODULE__{
+      resource: resource,
+      reference: reference
+    }
+  end
+end
+
+defimpl Inspect, for: Wasmex.MemoryStream do
+  import Inspect.Algebra
+
+  def inspect(dict, opts) do
+    concat(["#Wasmex.MemoryStream<", to_doc(dict.reference, opts), ">"])
+  end
+end
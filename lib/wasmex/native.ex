@@ -4,21 +4,52 @@ defmodule Wasmex.Native do
   Functions in this module are not intended to be called directly.
   """
 
-  use Rustler, otp_app: :wasmex
+  # the test suite needs NIFs which are not part of the library
+  use Rustler, otp_app: :wasmex, features: if(Mix.env() == :test, do: ["testing"], else: [])
 
   def instance_new_from_bytes(_bytes, _imports), do: error()
+  def instance_new_from_file(_path, _imports), do: error()
   def instance_function_export_exists(_resource, _function_name), do: error()
+  def instance_is_healthy(_resource), do: error()
+  def instance_exported_functions(_resource), do: error()
+  def instance_list_functions(_resource), do: error()
   def instance_call_exported_function(_resource, _function_name, _params, _from), do: error()
+  def instance_call_function_by_index(_resource, _index, _params, _from), do: error()
+  def instance_resolve_function(_resource, _function_name), do: error()
+  def instance_call_resolved_function(_function_resource, _params, _from), do: error()
+
+  def instance_call_with_memory(_resource, _function_name, _binary, _from, _alloc, _dealloc),
+    do: error()
+
+  def instance_table_size(_resource, _name), do: error()
+  def instance_table_get(_resource, _name, _index), do: error()
+  def instance_table_set(_resource, _name, _index, _value), do: error()
+  def instance_table_grow(_resource, _name, _delta, _init), do: error()
   def namespace_receive_callback_result(_callback_token, _success, _params), do: error()
+  def caller_get_global(_caller, _name), do: error()
+  def caller_set_global(_caller, _name, _value), do: error()
   def memory_from_instance(_resource), do: error()
+  def memory_from_caller(_caller, _name), do: error()
   def memory_bytes_per_element(_size), do: error()
   def memory_length(_resource, _size, _offset), do: error()
   def memory_grow(_resource, _pages), do: error()
+  def memory_snapshot(_resource), do: error()
+  def memory_restore(_resource, _snapshot), do: error()
   def memory_get(_resource, _size, _offset, _index), do: error()
   def memory_set(_resource, _size, _offset, _index, _value), do: error()
   def memory_read_binary(_resource, _size, _offset, _index, _length), do: error()
   def memory_write_binary(_resource, _size, _offset, _index, _binary), do: error()
 
+  def memory_stream_read(_resource, _size, _offset, _index, _length, _chunk_size, _receiver),
+    do: error()
+
+  def memory_stream_ack(_stream), do: error()
+  def memory_stream_cancel(_stream), do: error()
+
+  if Mix.env() == :test do
+    def instance_poison_for_testing(_resource), do: error()
+  end
+
   # When the NIF is loaded, it will override functions in this module.
   # Calling error is handles the case when the nif could not be loaded.
   defp error, do: :erlang.nif_error(:nif_not_loaded)
@@ -10,6 +10,10 @@ rustler::atoms! {
     int16,
     uint32,
     int32,
+    uint64,
+    int64,
+    float32,
+    float64,
 
     // imported function param/return types
     i32,
@@ -17,6 +21,13 @@ rustler::atoms! {
     f32,
     f64,
     v128,
+    externref,
+    funcref,
+
+    // non-finite floats
+    nan,
+    infinity,
+    neg_infinity,
 
     // import objects
     __fn__ = "fn",
@@ -25,8 +36,17 @@ rustler::atoms! {
 
     // callback context
     memory,
+    caller,
 
     // calls to erlang processes
     returned_function_call,
     invoke_callback,
+    memory_chunk,
+    memory_done,
+    memory_error,
+
+    // errors
+    instance_poisoned,
+    memory_poisoned,
+    caller_poisoned,
 }
@@ -0,0 +1,95 @@
+//! Caller API of an WebAssembly instance calling an imported function.
+
+use std::sync::{Mutex, MutexGuard};
+
+use rustler::{resource::ResourceArc, Atom, Encoder, Error, NifResult, Term};
+use wasmer::{Exports, Type, Val};
+
+use crate::{
+    atoms,
+    instance::{decode_f32, decode_float, encode_float},
+};
+
+// Memories and globals exported by the calling instance.
+pub struct CallerResource {
+    pub exports: Mutex<Exports>,
+}
+
+impl CallerResource {
+    // see `InstanceResource::lock`, returns `{:error, :caller_poisoned}` instead
+    pub fn lock(&self) -> NifResult<MutexGuard<'_, Exports>> {
+        self.exports
+            .lock()
+            .map_err(|_| Error::Term(Box::new(atoms::caller_poisoned())))
+    }
+}
+
+#[rustler::nif(name = "caller_get_global")]
+pub fn get_global<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<CallerResource>,
+    name: String,
+) -> NifResult<(Atom, Term<'a>)> {
+    let exports = resource.lock()?;
+    let global = exports
+        .get_global(&name)
+        .map_err(|_| global_not_found(&name))?;
+    let value = match global.get() {
+        Val::I32(i) => i.encode(env),
+        Val::I64(i) => i.encode(env),
+        Val::F32(i) => encode_float(env, f64::from(i)),
+        Val::F64(i) => encode_float(env, i),
+        _ => {
+            return Err(Error::Term(Box::new(format!(
+                "The type of global `{}` is not supported.",
+                name
+            ))))
+        }
+    };
+    Ok((atoms::ok(), value))
+}
+
+#[rustler::nif(name = "caller_set_global")]
+pub fn set_global(
+    resource: ResourceArc<CallerResource>,
+    name: String,
+    value: Term,
+) -> NifResult<Atom> {
+    let exports = resource.lock()?;
+    let global = exports
+        .get_global(&name)
+        .map_err(|_| global_not_found(&name))?;
+    let value = match global.ty().ty {
+        Type::I32 => value.decode::<i32>().ok().map(Val::I32),
+        Type::I64 => value.decode::<i64>().ok().map(Val::I64),
+        Type::F32 => decode_float(value).and_then(decode_f32).map(Val::F32),
+        Type::F64 => decode_float(value).map(Val::F64),
+        _ => {
+            return Err(Error::Term(Box::new(format!(
+                "The type of global `{}` is not supported.",
+                name
+            ))))
+        }
+    }
+    .ok_or_else(|| {
+        Error::Term(Box::new(format!(
+            "Cannot convert the given value to the type of global `{}`.",
+            name
+        )))
+    })?;
+    global.set(value).map_err(|e| {
+        Error::Term(Box::new(format!(
+            "Could not set global `{}`: {}",
+            name,
+            e.message()
+        )))
+    })?;
+    Ok(atoms::ok())
+}
+
+fn global_not_found(name: &str) -> Error {
+    Error::Term(Box::new(format!(
+        "The WebAssembly module has no exported global named `{}`.",
+        name
+    )))
+}
@@ -5,21 +5,51 @@ use rustler::{
     Term,
 };
 use wasmer::{
-    imports, namespace, Exports, Function, FunctionType, ImportObject, LazyInit, Memory,
-    RuntimeError, Store, Type, Val, WasmerEnv,
+    imports, namespace, Exports, Function, FunctionType, Global, HostEnvInitError, ImportObject,
+    Instance, LazyInit, Memory, RuntimeError, Store, Type, Val, WasmerEnv,
 };
 
 use crate::{
     atoms,
-    instance::{map_to_wasmer_values, WasmValue},
+    caller::CallerResource,
+    instance::{encode_value, map_to_wasmer_values, WasmValue},
     memory::MemoryResource,
 };
 
 /// The environment provided to the WASI imports.
-#[derive(WasmerEnv, Clone, Default)]
+#[derive(Clone, Default)]
 pub struct Environment {
-    #[wasmer(export)]
     pub memory: LazyInit<Memory>,
+    // exports of the calling instance which imported functions can look up by name
+    pub exports: LazyInit<Exports>,
+}
+
+// Exports are held as weak references, because the instance owns this environment.
+impl WasmerEnv for Environment {
+    fn init_with_instance(&mut self, instance: &Instance) -> Result<(), HostEnvInitError> {
+        if let Ok(memory) = instance
+            .exports
+            .get_with_generics_weak::<Memory, (), ()>("memory")
+        {
+            self.memory.initialize(memory);
+        }
+
+        let mut exports = Exports::new();
+        for (name, _memory) in instance.exports.iter().memories() {
+            let memory = instance
+                .exports
+                .get_with_generics_weak::<Memory, (), ()>(name)?;
+            exports.insert(name.clone(), memory);
+        }
+        for (name, _global) in instance.exports.iter().globals() {
+            let global = instance
+                .exports
+                .get_with_generics_weak::<Global, (), ()>(name)?;
+            exports.insert(name.clone(), global);
+        }
+        self.exports.initialize(exports);
+        Ok(())
+    }
 }
 
 pub struct CallbackTokenResource {
@@ -36,6 +66,7 @@ impl Environment {
     pub fn new() -> Self {
         Self {
             memory: LazyInit::default(),
+            exports: LazyInit::default(),
         }
     }
 
@@ -141,42 +172,44 @@ impl Environment {
 
                 let mut msg_env = OwnedEnv::new();
                 msg_env.send_and_clear(&pid.clone(), |env| {
-                    let mut callback_params: Vec<Term> = Vec::with_capacity(params.len());
-                    for value in params {
-                        callback_params.push(match value {
-                            Val::I32(i) => i.encode(env),
-                            Val::I64(i) => i.encode(env),
-                            Val::F32(i) => i.encode(env),
-                            Val::F64(i) => i.encode(env),
-                            // encoding V128 is not yet supported by rustler
-                            Val::V128(_) => {
-                                (atoms::error(), "unable_to_convert_v128_type").encode(env)
-                            }
-                            Val::ExternRef(_) => {
-                                (atoms::error(), "unable_to_convert_extern_ref_type").encode(env)
-                            }
-                            Val::FuncRef(_) => {
-                                (atoms::error(), "unable_to_convert_func_ref_type").encode(env)
-                            }
-                        })
-                    }
+                    // params are given to the callback like results of exported functions
+                    let callback_params: Vec<Term> = params
+                        .iter()
+                        .map(|value| encode_value(env, value.clone()))
+                        .collect();
                     // Callback context will contain memory (plus globals, tables etc later).
                     // This will allow Elixir callback to operate on these objects.
-                    let callback_context = Term::map_new(env);
+                    let mut callback_context = Term::map_new(env);
+
+                    // the default memory is only available if the module exports one named `memory`
+                    if let Some(memory) = wasmer_environment.memory.get_ref() {
+                        let memory_resource = ResourceArc::new(MemoryResource {
+                            memory: Mutex::new(memory.clone()),
+                        });
+                        callback_context = match Term::map_put(
+                            callback_context,
+                            atoms::memory().encode(env),
+                            memory_resource.encode(env),
+                        ) {
+                            Ok(map) => map,
+                            _ => unreachable!(),
+                        };
+                    }
 
-                    let memory_resource = ResourceArc::new(MemoryResource {
-                        memory: Mutex::new(
+                    // the caller gives access to other exports by name
+                    let caller_resource = ResourceArc::new(CallerResource {
+                        exports: Mutex::new(
                             wasmer_environment
-                                .memory
+                                .exports
                                 .get_ref()
-                                .expect("wasm memory was not initialized")
+                                .expect("wasm exports were not initialized")
                                 .clone(),
                         ),
                     });
                     let callback_context = match Term::map_put(
                         callback_context,
-                        atoms::memory().encode(env),
-                        memory_resource.encode(env),
+                        atoms::caller().encode(env),
+                        caller_resource.encode(env),
                     ) {
                         Ok(map) => map,
                         _ => unreachable!(),
@@ -193,9 +226,18 @@ impl Environment {
                 });
 
                 // Wait for the thread to start up - `receive_callback_result` is responsible for that.
-                let mut result = callback_token.token.return_values.lock().unwrap();
+                let poisoned = |_| RuntimeError::new("the callback token is poisoned");
+                let mut result = callback_token
+                    .token
+                    .return_values
+                    .lock()
+                    .map_err(poisoned)?;
                 while result.is_none() {
-                    result = callback_token.token.continue_signal.wait(result).unwrap();
+                    result = callback_token
+                        .token
+                        .continue_signal
+                        .wait(result)
+                        .map_err(poisoned)?;
                 }
 
                 let result: &(bool, Vec<WasmValue>) = result
@@ -1,4 +1,13 @@
-use wasmer::{ExportError, Function, Instance};
+use rustler::{resource::ResourceArc, Atom};
+use wasmer::{ExportError, Extern, Function, Instance, Type};
+
+use crate::{atoms, instance::InstanceResource};
+
+pub struct FunctionResource {
+    // keeps the instance alive and serializes calls to it
+    pub instance: ResourceArc<InstanceResource>,
+    pub function: Function,
+}
 
 pub fn exists(instance: &Instance, name: &str) -> bool {
     find(instance, &name).is_ok()
@@ -7,3 +16,52 @@ pub fn exists(instance: &Instance, name: &str) -> bool {
 pub fn find<'a>(instance: &'a Instance, name: &str) -> Result<&'a Function, ExportError> {
     instance.exports.get(name)
 }
+
+// exported functions with their index, which is stable for the lifetime of the instance
+pub fn list(instance: &Instance) -> Vec<(&String, usize, &Function)> {
+    exported_functions(instance)
+        .enumerate()
+        .map(|(index, (name, function))| (name, index, function))
+        .collect()
+}
+
+// all exported functions in the order of `list`, so that they can be looked up by index
+pub fn indexed(instance: &Instance) -> Vec<Function> {
+    exported_functions(instance)
+        .map(|(_name, function)| function.clone())
+        .collect()
+}
+
+fn exported_functions(instance: &Instance) -> impl Iterator<Item = (&String, &Function)> {
+    instance
+        .exports
+        .iter()
+        .filter_map(|(name, export)| match export {
+            Extern::Function(function) => Some((name, function)),
+            _ => None,
+        })
+}
+
+pub fn type_to_atom(value_type: &Type) -> Atom {
+    match value_type {
+        Type::I32 => atoms::i32(),
+        Type::I64 => atoms::i64(),
+        Type::F32 => atoms::f32(),
+        Type::F64 => atoms::f64(),
+        Type::V128 => atoms::v128(),
+        Type::ExternRef => atoms::externref(),
+        Type::FuncRef => atoms::funcref(),
+    }
+}
+
+pub fn type_name(value_type: &Type) -> &'static str {
+    match value_type {
+        Type::I32 => "i32",
+        Type::I64 => "i64",
+        Type::F32 => "f32",
+        Type::F64 => "f64",
+        Type::V128 => "v128",
+        Type::ExternRef => "externref",
+        Type::FuncRef => "funcref",
+    }
+}
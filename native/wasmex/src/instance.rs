@@ -1,25 +1,44 @@
 use rustler::{
-    dynamic::TermType,
     env::{OwnedEnv, SavedTerm},
     resource::ResourceArc,
-    types::binary::Binary,
+    types::binary::{Binary, OwnedBinary},
     types::tuple::make_tuple,
-    NifResult, {Encoder, Env as RustlerEnv, MapIterator, Term},
+    Atom, NifResult, {Encoder, Env as RustlerEnv, MapIterator, Term},
 };
-use std::sync::Mutex;
+use std::convert::TryFrom;
+use std::sync::{Mutex, MutexGuard};
 use std::thread;
 
-use wasmer::{Instance, Module, Store, Type, Val, Value};
+use wasmer::{ExternRef, Function, Instance, Memory, Module, Store, Type, Val, Value};
 
 use crate::{
-    atoms, environment::Environment, functions, memory::memory_from_instance,
-    printable_term_type::PrintableTermType,
+    atoms,
+    environment::Environment,
+    functions,
+    functions::FunctionResource,
+    memory::memory_from_instance,
+    reference::{BoxedTerm, RefResource},
 };
 
 pub struct InstanceResource {
     pub instance: Mutex<Instance>,
+    // exported functions by their index, see `functions::list`
+    pub functions: Vec<Function>,
 }
 
+impl InstanceResource {
+    // A panic while the instance was locked poisons its mutex. The instance may be left in an
+    // inconsistent state, so it is not used anymore and `{:error, :instance_poisoned}` is returned instead.
+    pub fn lock(&self) -> NifResult<MutexGuard<'_, Instance>> {
+        self.instance
+            .lock()
+            .map_err(|_| rustler::Error::Term(Box::new(atoms::instance_poisoned())))
+    }
+}
+
+// param and result types of a function
+type Signature = (Vec<Atom>, Vec<Atom>);
+
 #[derive(NifTuple)]
 pub struct InstanceResourceResponse {
     ok: rustler::Atom,
@@ -36,18 +55,32 @@ pub struct InstanceResourceResponse {
 pub fn new_from_bytes(binary: Binary, imports: MapIterator) -> NifResult<InstanceResourceResponse> {
     let bytes = binary.as_slice();
 
+    new_instance(imports, |store| {
+        Module::new(store, &bytes).map_err(|e| format!("Could not compule module: {:?}", e))
+    })
+}
+
+// creates a new instance from the WASM (or WAT) file at the given path
+// the file is read in Rust, so that large modules do not need to be copied into an elixir binary first
+//
+// * path (string): path to the WASM module file
+// * imports (map): same as for `new_from_bytes`
+#[rustler::nif(name = "instance_new_from_file", schedule = "DirtyCpu")]
+pub fn new_from_file(path: String, imports: MapIterator) -> NifResult<InstanceResourceResponse> {
+    new_instance(imports, |store| {
+        Module::from_file(store, &path)
+            .map_err(|e| format!("Could not compile module from `{}`: {:?}", path, e))
+    })
+}
+
+fn new_instance<F>(imports: MapIterator, compile: F) -> NifResult<InstanceResourceResponse>
+where
+    F: FnOnce(&Store) -> Result<Module, String>,
+{
     let mut environment = Environment::new();
     let import_object = environment.import_object(imports)?; // TODO: maybe we can improve this with a map type!
     let store = Store::default();
-    let module = match Module::new(&store, &bytes) {
-        Ok(module) => module,
-        Err(e) => {
-            return Err(rustler::Error::Term(Box::new(format!(
-                "Could not compule module: {:?}",
-                e
-            ))))
-        }
-    };
+    let module = compile(&store).map_err(|reason| rustler::Error::Term(Box::new(reason)))?;
     let instance = match Instance::new(&module, &import_object) {
         Ok(instance) => instance,
         Err(e) => {
@@ -57,11 +90,10 @@ pub fn new_from_bytes(binary: Binary, imports: MapIterator) -> NifResult<Instanc
             ))))
         }
     };
-    let memory = memory_from_instance(&instance)?.clone();
-    environment.memory.initialize(memory);
-
+    let functions = functions::indexed(&instance);
     let resource = ResourceArc::new(InstanceResource {
         instance: Mutex::new(instance),
+        functions,
     });
     Ok(InstanceResourceResponse {
         ok: atoms::ok(),
@@ -74,9 +106,55 @@ pub fn function_export_exists(
     resource: ResourceArc<InstanceResource>,
     function_name: String,
 ) -> bool {
-    let instance = resource.instance.lock().unwrap();
+    // functions of a poisoned instance can not be called, so they are treated as missing
+    match resource.lock() {
+        Ok(instance) => functions::exists(&instance, &function_name),
+        Err(_) => false,
+    }
+}
+
+// returns a map of all exported functions: `%{name => {param_types, result_types}}`
+#[rustler::nif(name = "instance_exported_functions")]
+pub fn exported_functions<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<InstanceResource>,
+) -> NifResult<Term<'a>> {
+    let instance = resource.lock()?;
 
-    functions::exists(&instance, &function_name)
+    let mut map = Term::map_new(env);
+    for (name, _index, function) in functions::list(&instance) {
+        map = map.map_put(name.encode(env), signature(function).encode(env))?;
+    }
+    Ok(map)
+}
+
+// returns a list of all exported functions: `[{name, index, {param_types, result_types}}]`
+// the index can be used to call the function with `call_function_by_index`
+#[rustler::nif(name = "instance_list_functions")]
+pub fn list_functions(
+    resource: ResourceArc<InstanceResource>,
+) -> NifResult<Vec<(String, usize, Signature)>> {
+    let instance = resource.lock()?;
+
+    Ok(functions::list(&instance)
+        .into_iter()
+        .map(|(name, index, function)| (name.clone(), index, signature(function)))
+        .collect())
+}
+
+fn signature(function: &Function) -> Signature {
+    let function_type = function.ty();
+    let params = function_type
+        .params()
+        .iter()
+        .map(functions::type_to_atom)
+        .collect();
+    let results = function_type
+        .results()
+        .iter()
+        .map(functions::type_to_atom)
+        .collect();
+    (params, results)
 }
 
 #[rustler::nif(name = "instance_call_exported_function", schedule = "DirtyCpu")]
@@ -87,17 +165,27 @@ pub fn call_exported_function<'a>(
     params: Term,
     from: Term,
 ) -> rustler::Atom {
+    spawn_call(env, params, from, move |thread_env, params, from| {
+        execute_function(thread_env, resource, function_name, params, from)
+    })
+}
+
+// Executes a call in a new OS thread, so that the NIF returns right away.
+// `params` and `from` are copied into an environment owned by the thread and given to `execute`,
+// which returns the `{:returned_function_call, result, from}` message sent to the calling process.
+fn spawn_call<F>(env: RustlerEnv, params: Term, from: Term, execute: F) -> rustler::Atom
+where
+    F: for<'b> FnOnce(RustlerEnv<'b>, SavedTerm, SavedTerm) -> Term<'b> + Send + 'static,
+{
     let pid = env.pid();
     // create erlang environment for the thread
     let mut thread_env = OwnedEnv::new();
     // copy over params into the thread environment
-    let function_params = thread_env.save(params);
+    let params = thread_env.save(params);
     let from = thread_env.save(from);
 
     thread::spawn(move || {
-        thread_env.send_and_clear(&pid, |thread_env| {
-            execute_function(thread_env, resource, function_name, function_params, from)
-        })
+        thread_env.send_and_clear(&pid, |thread_env| execute(thread_env, params, from))
     });
 
     atoms::ok()
@@ -110,15 +198,15 @@ fn execute_function(
     function_params: SavedTerm,
     from: SavedTerm,
 ) -> Term {
-    let from = from
-        .load(thread_env)
-        .decode::<Term>()
-        .unwrap_or_else(|_| "could not load 'from' param".encode(thread_env));
+    let from = load_from(thread_env, from);
     let given_params = match function_params.load(thread_env).decode::<Vec<Term>>() {
         Ok(vec) => vec,
         Err(_) => return make_error_tuple(&thread_env, "could not load 'function params'", from),
     };
-    let instance = resource.instance.lock().unwrap();
+    let instance = match resource.lock() {
+        Ok(instance) => instance,
+        Err(_) => return make_poisoned_error_tuple(&thread_env, from),
+    };
     let function = match functions::find(&instance, &function_name) {
         Ok(f) => f,
         Err(_) => {
@@ -129,6 +217,287 @@ fn execute_function(
             )
         }
     };
+    call_function(thread_env, function, given_params, from)
+}
+
+#[rustler::nif(name = "instance_call_function_by_index", schedule = "DirtyCpu")]
+pub fn call_function_by_index<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<InstanceResource>,
+    index: usize,
+    params: Term,
+    from: Term,
+) -> rustler::Atom {
+    spawn_call(env, params, from, move |thread_env, params, from| {
+        execute_function_by_index(thread_env, resource, index, params, from)
+    })
+}
+
+fn execute_function_by_index(
+    thread_env: RustlerEnv,
+    resource: ResourceArc<InstanceResource>,
+    index: usize,
+    function_params: SavedTerm,
+    from: SavedTerm,
+) -> Term {
+    let from = load_from(thread_env, from);
+    let given_params = match function_params.load(thread_env).decode::<Vec<Term>>() {
+        Ok(vec) => vec,
+        Err(_) => return make_error_tuple(&thread_env, "could not load 'function params'", from),
+    };
+    // calls are serialized per instance, no matter how the function was found
+    let _instance = match resource.lock() {
+        Ok(instance) => instance,
+        Err(_) => return make_poisoned_error_tuple(&thread_env, from),
+    };
+    let function = match resource.functions.get(index) {
+        Some(f) => f,
+        None => {
+            return make_error_tuple(
+                &thread_env,
+                &format!("exported function with index {} not found", index),
+                from,
+            )
+        }
+    };
+    call_function(thread_env, function, given_params, from)
+}
+
+// creates a handle for the exported function with the given name
+// so that it can be called repeatedly without looking it up by name each time
+#[rustler::nif(name = "instance_resolve_function")]
+pub fn resolve_function(
+    resource: ResourceArc<InstanceResource>,
+    function_name: String,
+) -> NifResult<(rustler::Atom, ResourceArc<FunctionResource>)> {
+    let function = {
+        let instance = resource.lock()?;
+        match functions::find(&instance, &function_name) {
+            Ok(function) => function.clone(),
+            Err(_) => {
+                return Err(rustler::Error::Term(Box::new(format!(
+                    "exported function `{}` not found",
+                    function_name
+                ))))
+            }
+        }
+    };
+    let function_resource = ResourceArc::new(FunctionResource {
+        instance: resource,
+        function,
+    });
+    Ok((atoms::ok(), function_resource))
+}
+
+#[rustler::nif(name = "instance_call_resolved_function", schedule = "DirtyCpu")]
+pub fn call_resolved_function<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<FunctionResource>,
+    params: Term,
+    from: Term,
+) -> rustler::Atom {
+    spawn_call(env, params, from, move |thread_env, params, from| {
+        execute_resolved_function(thread_env, resource, params, from)
+    })
+}
+
+fn execute_resolved_function(
+    thread_env: RustlerEnv,
+    resource: ResourceArc<FunctionResource>,
+    function_params: SavedTerm,
+    from: SavedTerm,
+) -> Term {
+    let from = load_from(thread_env, from);
+    let given_params = match function_params.load(thread_env).decode::<Vec<Term>>() {
+        Ok(vec) => vec,
+        Err(_) => return make_error_tuple(&thread_env, "could not load 'function params'", from),
+    };
+    // calls are serialized per instance, no matter how the function was found
+    let _instance = match resource.instance.lock() {
+        Ok(instance) => instance,
+        Err(_) => return make_poisoned_error_tuple(&thread_env, from),
+    };
+    call_function(thread_env, &resource.function, given_params, from)
+}
+
+// calls an exported function taking a `(ptr, len)` pair pointing to the given binary and returning
+// such a pair pointing to its result. expects the following elixir params
+//
+// * function_name (string): the function to call
+// * binary (binary): copied into memory allocated with the exported `alloc_name` function,
+//   which is either called like `alloc(len)` or like `cabi_realloc(0, 0, 1, len)`
+// * from: as given to `handle_call`
+// * alloc_name (string): name of the exported allocator
+// * dealloc_name (string): name of an exported `dealloc(ptr, len)` function. If the module exports it,
+//   both the given and the returned memory regions are freed after the result was copied
+#[rustler::nif(name = "instance_call_with_memory", schedule = "DirtyCpu")]
+pub fn call_with_memory<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<InstanceResource>,
+    function_name: String,
+    binary: Term,
+    from: Term,
+    alloc_name: String,
+    dealloc_name: String,
+) -> rustler::Atom {
+    spawn_call(env, binary, from, move |thread_env, binary, from| {
+        let from = load_from(thread_env, from);
+        let input = match binary.load(thread_env).decode::<Binary>() {
+            Ok(binary) => binary,
+            Err(_) => return make_error_tuple(&thread_env, "could not load 'binary'", from),
+        };
+        let instance = match resource.lock() {
+            Ok(instance) => instance,
+            Err(_) => return make_poisoned_error_tuple(&thread_env, from),
+        };
+        let output = match execute_with_memory(
+            &instance,
+            &function_name,
+            &alloc_name,
+            &dealloc_name,
+            input.as_slice(),
+        ) {
+            Ok(output) => output,
+            Err(reason) => return make_error_tuple(&thread_env, &reason, from),
+        };
+        let mut binary = OwnedBinary::new(output.len()).unwrap();
+        binary.as_mut_slice().copy_from_slice(&output);
+        make_tuple(
+            thread_env,
+            &[
+                atoms::returned_function_call().encode(thread_env),
+                (atoms::ok(), binary.release(thread_env)).encode(thread_env),
+                from,
+            ],
+        )
+    })
+}
+
+fn execute_with_memory(
+    instance: &Instance,
+    function_name: &str,
+    alloc_name: &str,
+    dealloc_name: &str,
+    input: &[u8],
+) -> Result<Vec<u8>, String> {
+    let memory = memory_from_instance(instance)
+        .map_err(|_| "The WebAssembly module has no exported memory.".to_string())?;
+    let find = |name: &str| {
+        functions::find(instance, name)
+            .map_err(|_| format!("exported function `{}` not found", name))
+    };
+    let function = find(function_name)?;
+    let alloc = find(alloc_name)?;
+    let dealloc = functions::find(instance, dealloc_name).ok();
+
+    let input_len = i32::try_from(input.len()).map_err(|_| {
+        format!(
+            "The given binary of {} bytes does not fit into WebAssembly memory.",
+            input.len()
+        )
+    })?;
+    let alloc_params = match alloc.ty().params().len() {
+        1 => vec![Val::I32(input_len)],
+        4 => vec![Val::I32(0), Val::I32(0), Val::I32(1), Val::I32(input_len)],
+        _ => {
+            return Err(format!(
+                "exported function `{}` must be called like `alloc(len)` or `cabi_realloc(ptr, old_len, align, len)`",
+                alloc_name
+            ))
+        }
+    };
+    let input_ptr = match *call(alloc, &alloc_params)? {
+        [Val::I32(ptr)] => ptr,
+        _ => {
+            return Err(format!(
+                "exported function `{}` must return a single i32 pointer",
+                alloc_name
+            ))
+        }
+    };
+
+    // Safety: see `memory::snapshot`, the slice is not used after calling into the module.
+    let bytes = unsafe { memory.data_unchecked_mut() };
+    let input_start = input_ptr as u32 as usize;
+    if input_start + input.len() > bytes.len() {
+        return Err(format!(
+            "exported function `{}` returned a pointer out of memory",
+            alloc_name
+        ));
+    }
+    bytes[input_start..input_start + input.len()].copy_from_slice(input);
+
+    let result = call_with_input(memory, function, function_name, input_ptr, input_len);
+
+    // the input is freed even if the call failed
+    if let Some(dealloc) = dealloc {
+        call(dealloc, &[Val::I32(input_ptr), Val::I32(input_len)])?;
+    }
+    let (output, output_ptr, output_len) = result?;
+    if let Some(dealloc) = dealloc {
+        if output_ptr != input_ptr {
+            call(dealloc, &[Val::I32(output_ptr), Val::I32(output_len)])?;
+        }
+    }
+    Ok(output)
+}
+
+// calls the function with the `(ptr, len)` pair of the input and copies the returned region.
+// returns the copied output together with its `(ptr, len)` pair, so that it can be freed.
+fn call_with_input(
+    memory: &Memory,
+    function: &Function,
+    function_name: &str,
+    input_ptr: i32,
+    input_len: i32,
+) -> Result<(Vec<u8>, i32, i32), String> {
+    let (output_ptr, output_len) =
+        match *call(function, &[Val::I32(input_ptr), Val::I32(input_len)])? {
+            [Val::I32(ptr), Val::I32(len)] => (ptr, len),
+            _ => {
+                return Err(format!(
+                    "exported function `{}` must return a `(ptr, len)` pair of i32 values",
+                    function_name
+                ))
+            }
+        };
+
+    // the memory may have grown during the call
+    // Safety: see `memory::snapshot`
+    let bytes = unsafe { memory.data_unchecked() };
+    let output_start = output_ptr as u32 as usize;
+    let output_end = output_start + output_len as u32 as usize;
+    if output_end > bytes.len() {
+        return Err(format!(
+            "exported function `{}` returned a region out of memory",
+            function_name
+        ));
+    }
+    Ok((
+        bytes[output_start..output_end].to_vec(),
+        output_ptr,
+        output_len,
+    ))
+}
+
+fn call(function: &Function, params: &[Val]) -> Result<Box<[Val]>, String> {
+    function
+        .call(params)
+        .map_err(|e| format!("Error during function excecution: `{}`.", e))
+}
+
+fn load_from(thread_env: RustlerEnv, from: SavedTerm) -> Term {
+    from.load(thread_env)
+        .decode::<Term>()
+        .unwrap_or_else(|_| "could not load 'from' param".encode(thread_env))
+}
+
+fn call_function<'a>(
+    thread_env: RustlerEnv<'a>,
+    function: &Function,
+    given_params: Vec<Term<'a>>,
+    from: Term<'a>,
+) -> Term<'a> {
     let function_params = match decode_function_param_terms(&function.ty().params(), given_params) {
         Ok(vec) => map_to_wasmer_values(&vec),
         Err(reason) => return make_error_tuple(&thread_env, &reason, from),
@@ -144,25 +513,10 @@ fn execute_function(
             )
         }
     };
-    let mut return_values: Vec<Term> = Vec::with_capacity(results.len());
-    for value in results.to_vec() {
-        return_values.push(match value {
-            Val::I32(i) => i.encode(thread_env),
-            Val::I64(i) => i.encode(thread_env),
-            Val::F32(i) => i.encode(thread_env),
-            Val::F64(i) => i.encode(thread_env),
-            // encoding V128 is not yet supported by rustler
-            Val::V128(_) => {
-                return make_error_tuple(&thread_env, &"unable_to_return_v128_type", from)
-            }
-            Val::FuncRef(_) => {
-                return make_error_tuple(&thread_env, &"unable_to_return_func_ref_type", from)
-            }
-            Val::ExternRef(_) => {
-                return make_error_tuple(&thread_env, &"unable_to_return_extern_ref_type", from)
-            }
-        })
-    }
+    let return_values: Vec<Term> = results
+        .iter()
+        .map(|value| encode_value(thread_env, value.clone()))
+        .collect();
     make_tuple(
         thread_env,
         &[
@@ -179,12 +533,36 @@ fn execute_function(
     )
 }
 
-#[derive(Debug, Copy, Clone)]
+// references are returned as opaque handles, unless they are null (`nil`) or a boxed elixir term
+pub fn encode_value(env: RustlerEnv, value: Val) -> Term {
+    match value {
+        Val::I32(i) => i.encode(env),
+        Val::I64(i) => i.encode(env),
+        Val::F32(i) => encode_float(env, f64::from(i)),
+        Val::F64(i) => encode_float(env, i),
+        Val::V128(i) => encode_v128(env, i),
+        Val::FuncRef(None) => atoms::__nil__().encode(env),
+        Val::FuncRef(Some(function)) => {
+            ResourceArc::new(RefResource::new(Val::FuncRef(Some(function)))).encode(env)
+        }
+        // every non-null externref was boxed from an elixir term by `decode_extern_ref`
+        Val::ExternRef(extern_ref) => match extern_ref.downcast::<BoxedTerm>() {
+            Some(boxed_term) => boxed_term.load(env),
+            None => atoms::__nil__().encode(env),
+        },
+    }
+}
+
+#[derive(Clone)]
 pub enum WasmValue {
     I32(i32),
     I64(i64),
     F32(f32),
     F64(f64),
+    V128(u128),
+    // `None` represents a null reference
+    ExternRef(Option<ResourceArc<RefResource>>),
+    FuncRef(Option<ResourceArc<RefResource>>),
 }
 
 pub fn decode_function_param_terms(
@@ -205,65 +583,122 @@ pub fn decode_function_param_terms(
         .zip(function_param_terms.into_iter())
         .enumerate()
     {
-        let value = match (param, given_param.get_type()) {
-            (Type::I32, TermType::Number) => match given_param.decode::<i32>() {
-                Ok(value) => WasmValue::I32(value),
-                Err(_) => {
-                    return Err(format!(
-                        "Cannot convert argument #{} to a WebAssembly i32 value.",
-                        nth + 1
-                    ));
-                }
-            },
-            (Type::I64, TermType::Number) => match given_param.decode::<i64>() {
-                Ok(value) => WasmValue::I64(value),
-                Err(_) => {
-                    return Err(format!(
-                        "Cannot convert argument #{} to a WebAssembly i64 value.",
-                        nth + 1
-                    ));
-                }
-            },
-            (Type::F32, TermType::Number) => match given_param.decode::<f32>() {
-                Ok(value) => {
-                    if value.is_finite() {
-                        WasmValue::F32(value)
-                    } else {
-                        return Err(format!(
-                            "Cannot convert argument #{} to a WebAssembly f32 value.",
-                            nth + 1
-                        ));
-                    }
-                }
-                Err(_) => {
-                    return Err(format!(
-                        "Cannot convert argument #{} to a WebAssembly f32 value.",
-                        nth + 1
-                    ));
-                }
-            },
-            (Type::F64, TermType::Number) => match given_param.decode::<f64>() {
-                Ok(value) => WasmValue::F64(value),
-                Err(_) => {
-                    return Err(format!(
-                        "Cannot convert argument #{} to a WebAssembly f64 value.",
-                        nth + 1
-                    ));
-                }
-            },
-            (_, term_type) => {
+        let value = match param {
+            Type::I32 => given_param.decode::<i32>().ok().map(WasmValue::I32),
+            Type::I64 => given_param.decode::<i64>().ok().map(WasmValue::I64),
+            Type::F32 => decode_float(given_param)
+                .and_then(decode_f32)
+                .map(WasmValue::F32),
+            Type::F64 => decode_float(given_param).map(WasmValue::F64),
+            Type::V128 => decode_v128(given_param).map(WasmValue::V128),
+            Type::ExternRef => decode_extern_ref(given_param).map(WasmValue::ExternRef),
+            Type::FuncRef => decode_func_ref(given_param).map(WasmValue::FuncRef),
+        };
+        match value {
+            Some(value) => function_params.push(value),
+            None => {
                 return Err(format!(
-                    "Cannot convert argument #{} to a WebAssembly value. Given `{:?}`.",
+                    "Cannot convert argument #{} to a WebAssembly {} value. Given `{:?}`.",
                     nth + 1,
-                    PrintableTermType::PrintTerm(term_type)
+                    functions::type_name(param),
+                    given_param
                 ));
             }
-        };
-        function_params.push(value);
+        }
     }
     Ok(function_params)
 }
 
+// erlang floats can not be NaN or infinite, so these values are represented
+// by the atoms `:nan`, `:infinity`, and `:neg_infinity`
+pub fn encode_float(env: RustlerEnv, value: f64) -> Term {
+    if value.is_nan() {
+        atoms::nan().encode(env)
+    } else if value == f64::INFINITY {
+        atoms::infinity().encode(env)
+    } else if value == f64::NEG_INFINITY {
+        atoms::neg_infinity().encode(env)
+    } else {
+        value.encode(env)
+    }
+}
+
+pub fn decode_float(term: Term) -> Option<f64> {
+    if let Ok(value) = term.decode::<f64>() {
+        return Some(value);
+    }
+    let atom = Atom::from_term(term).ok()?;
+    if atoms::nan().eq(&atom) {
+        Some(f64::NAN)
+    } else if atoms::infinity().eq(&atom) {
+        Some(f64::INFINITY)
+    } else if atoms::neg_infinity().eq(&atom) {
+        Some(f64::NEG_INFINITY)
+    } else {
+        None
+    }
+}
+
+// finite values too large for an f32 are rejected instead of becoming infinite
+pub fn decode_f32(value: f64) -> Option<f32> {
+    let narrowed = value as f32;
+    if narrowed.is_finite() == value.is_finite() {
+        Some(narrowed)
+    } else {
+        None
+    }
+}
+
+// rustler can not encode 128 bit integers, so v128 values are represented as
+// 16 byte binaries in little-endian byte order (the order of the lanes in WebAssembly memory)
+pub fn encode_v128(env: RustlerEnv, value: u128) -> Term {
+    let mut binary = OwnedBinary::new(16).unwrap();
+    binary.as_mut_slice().copy_from_slice(&value.to_le_bytes());
+    binary.release(env).encode(env)
+}
+
+fn decode_v128(term: Term) -> Option<u128> {
+    let binary = term.decode::<Binary>().ok()?;
+    let mut bytes = [0u8; 16];
+    if binary.len() != bytes.len() {
+        return None;
+    }
+    bytes.copy_from_slice(binary.as_slice());
+    Some(u128::from_le_bytes(bytes))
+}
+
+// `nil` decodes to a null reference and funcref handles are rejected.
+// Any other term is boxed, so that the guest can hold on to it and hand it back later.
+fn decode_extern_ref(term: Term) -> Option<Option<ResourceArc<RefResource>>> {
+    if is_nil(term) {
+        return Some(None);
+    }
+    match term.decode::<ResourceArc<RefResource>>() {
+        Ok(_) => None,
+        Err(_) => {
+            let extern_ref = ExternRef::new(BoxedTerm::new(term));
+            Some(Some(ResourceArc::new(RefResource::new(Val::ExternRef(
+                extern_ref,
+            )))))
+        }
+    }
+}
+
+// `nil` decodes to a null reference, handles must have been returned by a previous call
+fn decode_func_ref(term: Term) -> Option<Option<ResourceArc<RefResource>>> {
+    if is_nil(term) {
+        return Some(None);
+    }
+    match term.decode::<ResourceArc<RefResource>>() {
+        Ok(resource) if resource.value_type() == Type::FuncRef => Some(Some(resource)),
+        _ => None,
+    }
+}
+
+fn is_nil(term: Term) -> bool {
+    matches!(Atom::from_term(term), Ok(atom) if atoms::__nil__().eq(&atom))
+}
+
 pub fn map_to_wasmer_values(values: &[WasmValue]) -> Vec<Val> {
     values
         .iter()
@@ -272,10 +707,45 @@ pub fn map_to_wasmer_values(values: &[WasmValue]) -> Vec<Val> {
             WasmValue::I64(value) => Value::I64(*value),
             WasmValue::F32(value) => Value::F32(*value),
             WasmValue::F64(value) => Value::F64(*value),
+            WasmValue::V128(value) => Value::V128(*value),
+            WasmValue::ExternRef(Some(resource)) => resource.value.clone(),
+            WasmValue::ExternRef(None) => Value::ExternRef(ExternRef::null()),
+            WasmValue::FuncRef(Some(resource)) => resource.value.clone(),
+            WasmValue::FuncRef(None) => Value::FuncRef(None),
         })
         .collect()
 }
 
+// returns whether the instance can still be used, see `InstanceResource::lock`
+#[rustler::nif(name = "instance_is_healthy")]
+pub fn is_healthy(resource: ResourceArc<InstanceResource>) -> bool {
+    !resource.instance.is_poisoned()
+}
+
+// poisons the lock of the instance by panicking while holding it.
+// only used to test how poisoned instances are handled.
+#[cfg(feature = "testing")]
+#[rustler::nif(name = "instance_poison_for_testing")]
+pub fn poison_for_testing(resource: ResourceArc<InstanceResource>) -> rustler::Atom {
+    let _ = thread::spawn(move || {
+        let _instance = resource.instance.lock();
+        panic!("poisoning the instance for testing");
+    })
+    .join();
+    atoms::ok()
+}
+
+fn make_poisoned_error_tuple<'a>(env: &RustlerEnv<'a>, from: Term<'a>) -> Term<'a> {
+    make_tuple(
+        *env,
+        &[
+            atoms::returned_function_call().encode(*env),
+            env.error_tuple(atoms::instance_poisoned()),
+            from,
+        ],
+    )
+}
+
 fn make_error_tuple<'a>(env: &RustlerEnv<'a>, reason: &str, from: Term<'a>) -> Term<'a> {
     make_tuple(
         *env,
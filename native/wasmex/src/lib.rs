@@ -1,10 +1,12 @@
 pub mod atoms;
+pub mod caller;
 pub mod environment;
 pub mod functions;
 pub mod instance;
 pub mod memory;
 pub mod namespace;
-pub mod printable_term_type;
+pub mod reference;
+pub mod table;
 
 extern crate lazy_static;
 #[macro_use]
@@ -12,28 +14,66 @@ extern crate rustler;
 
 use rustler::{Env, Term};
 
-rustler::init! {
-    "Elixir.Wasmex.Native",
-    [
-        instance::new_from_bytes,
-        instance::function_export_exists,
-        instance::call_exported_function,
-        namespace::receive_callback_result,
-        memory::from_instance,
-        memory::bytes_per_element,
-        memory::length,
-        memory::grow,
-        memory::get,
-        memory::set,
-        memory::read_binary,
-        memory::write_binary,
-    ],
-    load = on_load
+// NIFs can not be left out of `rustler::init!` with `cfg` attributes,
+// so additional NIFs are appended to the list by this macro instead.
+macro_rules! init_nifs {
+    ($($additional_nifs:tt)*) => {
+        rustler::init! {
+            "Elixir.Wasmex.Native",
+            [
+                instance::new_from_bytes,
+                instance::new_from_file,
+                instance::function_export_exists,
+                instance::exported_functions,
+                instance::list_functions,
+                instance::call_exported_function,
+                instance::call_function_by_index,
+                instance::resolve_function,
+                instance::call_resolved_function,
+                instance::call_with_memory,
+                instance::is_healthy,
+                table::size,
+                table::get,
+                table::set,
+                table::grow,
+                namespace::receive_callback_result,
+                caller::get_global,
+                caller::set_global,
+                memory::from_instance,
+                memory::from_caller,
+                memory::bytes_per_element,
+                memory::length,
+                memory::grow,
+                memory::snapshot,
+                memory::restore,
+                memory::get,
+                memory::set,
+                memory::read_binary,
+                memory::write_binary,
+                memory::stream_read,
+                memory::stream_ack,
+                memory::stream_cancel,
+                $($additional_nifs)*
+            ],
+            load = on_load
+        }
+    };
 }
 
+#[cfg(not(feature = "testing"))]
+init_nifs!();
+
+#[cfg(feature = "testing")]
+init_nifs!(instance::poison_for_testing);
+
 fn on_load(env: Env, _info: Term) -> bool {
     rustler::resource!(instance::InstanceResource, env);
+    rustler::resource!(functions::FunctionResource, env);
     rustler::resource!(memory::MemoryResource, env);
+    rustler::resource!(memory::MemorySnapshotResource, env);
+    rustler::resource!(memory::MemoryStreamResource, env);
     rustler::resource!(environment::CallbackTokenResource, env);
+    rustler::resource!(caller::CallerResource, env);
+    rustler::resource!(reference::RefResource, env);
     true
 }
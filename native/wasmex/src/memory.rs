@@ -1,18 +1,43 @@
 //! Memory API of an WebAssembly instance.
 
-use std::sync::Mutex;
+use std::cmp;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread;
 
 use rustler::resource::ResourceArc;
-use rustler::{Atom, Binary, Encoder, Env as RustlerEnv, Error, NifResult, OwnedBinary, Term};
+use rustler::{
+    Atom, Binary, Encoder, Env as RustlerEnv, Error, LocalPid, NifResult, OwnedBinary, OwnedEnv,
+    Term,
+};
 
-use wasmer::{Extern, Instance, Memory, Pages};
+use wasmer::{Extern, Instance, Memory, Pages, WASM_PAGE_SIZE};
 
-use crate::{atoms, instance};
+use crate::{
+    atoms,
+    caller::CallerResource,
+    instance,
+    instance::{decode_f32, decode_float, encode_float},
+    reference::BoxedTerm,
+};
 
 pub struct MemoryResource {
     pub memory: Mutex<Memory>,
 }
 
+impl MemoryResource {
+    // Like `InstanceResource::lock`, but raises `:memory_poisoned` because memory functions raise on errors.
+    pub fn lock(&self) -> NifResult<MutexGuard<'_, Memory>> {
+        self.memory
+            .lock()
+            .map_err(|_| Error::RaiseTerm(Box::new(atoms::memory_poisoned())))
+    }
+}
+
+// a copy of the whole memory taken by `snapshot`
+pub struct MemorySnapshotResource {
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Copy, Clone)]
 pub enum ElementSize {
     Uint8,
@@ -21,6 +46,10 @@ pub enum ElementSize {
     Int16,
     Uint32,
     Int32,
+    Uint64,
+    Int64,
+    Float32,
+    Float64,
 }
 
 #[derive(NifTuple)]
@@ -33,7 +62,7 @@ pub struct MemoryResourceResponse {
 pub fn from_instance(
     instance_resource: ResourceArc<instance::InstanceResource>,
 ) -> rustler::NifResult<MemoryResourceResponse> {
-    let instance = instance_resource.instance.lock().unwrap();
+    let instance = instance_resource.lock()?;
     let memory = memory_from_instance(&*instance)?;
     let resource = ResourceArc::new(MemoryResource {
         memory: Mutex::new(memory.to_owned()),
@@ -45,6 +74,29 @@ pub fn from_instance(
     })
 }
 
+// returns the memory exported under the given name by the instance calling an imported function
+#[rustler::nif(name = "memory_from_caller")]
+pub fn from_caller(
+    caller_resource: ResourceArc<CallerResource>,
+    name: String,
+) -> rustler::NifResult<MemoryResourceResponse> {
+    let exports = caller_resource.lock()?;
+    let memory = exports.get_memory(&name).map_err(|_| {
+        Error::Term(Box::new(format!(
+            "The WebAssembly module has no exported memory named `{}`.",
+            name
+        )))
+    })?;
+    let resource = ResourceArc::new(MemoryResource {
+        memory: Mutex::new(memory.to_owned()),
+    });
+
+    Ok(MemoryResourceResponse {
+        ok: atoms::ok(),
+        resource,
+    })
+}
+
 fn size_from_term(size: &Term) -> Result<ElementSize, Error> {
     let size = size
         .atom_to_string()
@@ -56,9 +108,13 @@ fn size_from_term(size: &Term) -> Result<ElementSize, Error> {
         "int16" => ElementSize::Int16,
         "uint32" => ElementSize::Uint32,
         "int32" => ElementSize::Int32,
+        "uint64" => ElementSize::Uint64,
+        "int64" => ElementSize::Int64,
+        "float32" => ElementSize::Float32,
+        "float64" => ElementSize::Float64,
         _ => {
             return Err(Error::RaiseTerm(Box::new(
-                "Size must be one of `uint8`, `int8`, `uint16`, `int16`, `uint32`, `int32`.",
+                "Size must be one of `uint8`, `int8`, `uint16`, `int16`, `uint32`, `int32`, `uint64`, `int64`, `float32`, `float64`.",
             )))
         }
     };
@@ -79,6 +135,10 @@ fn byte_size(element_size: ElementSize) -> usize {
         ElementSize::Int16 => 2,
         ElementSize::Uint32 => 4,
         ElementSize::Int32 => 4,
+        ElementSize::Uint64 => 8,
+        ElementSize::Int64 => 8,
+        ElementSize::Float32 => 4,
+        ElementSize::Float64 => 8,
     }
 }
 
@@ -89,7 +149,7 @@ pub fn length(
     offset: usize,
 ) -> NifResult<usize> {
     let size = size_from_term(&size)?;
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let length = view_length(&memory, offset, size);
     Ok(length)
 }
@@ -102,16 +162,54 @@ fn view_length(memory: &Memory, offset: usize, element_size: ElementSize) -> usi
         ElementSize::Int16 => memory.view::<i16>()[offset..].len(),
         ElementSize::Uint32 => memory.view::<u32>()[offset..].len(),
         ElementSize::Int32 => memory.view::<i32>()[offset..].len(),
+        ElementSize::Uint64 => memory.view::<u64>()[offset..].len(),
+        ElementSize::Int64 => memory.view::<i64>()[offset..].len(),
+        ElementSize::Float32 => memory.view::<f32>()[offset..].len(),
+        ElementSize::Float64 => memory.view::<f64>()[offset..].len(),
     }
 }
 
 #[rustler::nif(name = "memory_grow")]
 pub fn grow(resource: ResourceArc<MemoryResource>, pages: u32) -> NifResult<u32> {
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let old_pages = grow_by_pages(&memory, pages)?;
     Ok(old_pages)
 }
 
+// copies the whole memory into a snapshot which can be written back with `restore`
+#[rustler::nif(name = "memory_snapshot", schedule = "DirtyCpu")]
+pub fn snapshot(
+    resource: ResourceArc<MemoryResource>,
+) -> NifResult<ResourceArc<MemorySnapshotResource>> {
+    let memory = resource.lock()?;
+    // Safety: the slice is copied right away and not held across a `grow`, which could move the memory.
+    // A guest writing concurrently only tears the copied bytes, as it would when copying cell by cell.
+    let data = unsafe { memory.data_unchecked() }.to_vec();
+    Ok(ResourceArc::new(MemorySnapshotResource { data }))
+}
+
+// writes the snapshot back into memory. the memory is grown if it is smaller than the snapshot.
+// memory can not shrink, so all bytes beyond the snapshot are zeroed instead.
+#[rustler::nif(name = "memory_restore", schedule = "DirtyCpu")]
+pub fn restore(
+    resource: ResourceArc<MemoryResource>,
+    saved: ResourceArc<MemorySnapshotResource>,
+) -> NifResult<Atom> {
+    let memory = resource.lock()?;
+    let data = &saved.data;
+    let current_len = memory.view::<u8>().len();
+    if current_len < data.len() {
+        let missing_pages = (data.len() - current_len + WASM_PAGE_SIZE - 1) / WASM_PAGE_SIZE;
+        grow_by_pages(&memory, missing_pages as u32)?;
+    }
+
+    // Safety: see `snapshot`, the memory was already grown to fit the snapshot.
+    let bytes = unsafe { memory.data_unchecked_mut() };
+    bytes[..data.len()].copy_from_slice(data);
+    bytes[data.len()..].fill(0);
+    Ok(atoms::ok())
+}
+
 /// Grows the memory by the given amount of pages. Returns the old page count.
 fn grow_by_pages(memory: &Memory, number_of_pages: u32) -> Result<u32, Error> {
     memory
@@ -128,7 +226,7 @@ pub fn get<'a>(
     offset: usize,
     index: usize,
 ) -> NifResult<Term<'a>> {
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let size = size_from_term(&size)?;
     let index = bounds_checked_index(&memory, size, offset, index)?;
 
@@ -150,6 +248,10 @@ fn get_value<'a>(
         ElementSize::Int16 => memory.view::<i16>()[i].get().encode(*env),
         ElementSize::Uint32 => memory.view::<u32>()[i].get().encode(*env),
         ElementSize::Int32 => memory.view::<i32>()[i].get().encode(*env),
+        ElementSize::Uint64 => memory.view::<u64>()[i].get().encode(*env),
+        ElementSize::Int64 => memory.view::<i64>()[i].get().encode(*env),
+        ElementSize::Float32 => encode_float(*env, f64::from(memory.view::<f32>()[i].get())),
+        ElementSize::Float64 => encode_float(*env, memory.view::<f64>()[i].get()),
     }
 }
 
@@ -161,7 +263,7 @@ pub fn set<'a>(
     index: usize,
     value: Term<'a>,
 ) -> NifResult<Atom> {
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let size = size_from_term(&size)?;
     let index = bounds_checked_index(&memory, size, offset, index)?;
 
@@ -183,10 +285,26 @@ fn set_value(
         ElementSize::Int16 => memory.view::<i16>()[offset + index].set(value.decode::<i16>()?),
         ElementSize::Uint32 => memory.view::<u32>()[offset + index].set(value.decode::<u32>()?),
         ElementSize::Int32 => memory.view::<i32>()[offset + index].set(value.decode::<i32>()?),
+        ElementSize::Uint64 => memory.view::<u64>()[offset + index].set(value.decode::<u64>()?),
+        ElementSize::Int64 => memory.view::<i64>()[offset + index].set(value.decode::<i64>()?),
+        ElementSize::Float32 => memory.view::<f32>()[offset + index].set(
+            decode_float(value)
+                .and_then(decode_f32)
+                .ok_or_else(|| float_error("float32", value))?,
+        ),
+        ElementSize::Float64 => memory.view::<f64>()[offset + index]
+            .set(decode_float(value).ok_or_else(|| float_error("float64", value))?),
     }
     Ok(())
 }
 
+fn float_error(type_name: &str, value: Term) -> Error {
+    Error::RaiseTerm(Box::new(format!(
+        "Cannot convert `{:?}` to a {} value.",
+        value, type_name
+    )))
+}
+
 fn bounds_checked_index(
     memory: &Memory,
     size: ElementSize,
@@ -223,7 +341,7 @@ pub fn read_binary<'a>(
     index: usize,
     len: usize,
 ) -> NifResult<Binary<'a>> {
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let size = size_from_term(&size)?;
     let index = bounds_checked_index(&memory, size, offset, index)?;
     let view = memory.view::<u8>();
@@ -256,7 +374,7 @@ pub fn write_binary(
     index: usize,
     binary: Binary,
 ) -> NifResult<Atom> {
-    let memory = resource.memory.lock().unwrap();
+    let memory = resource.lock()?;
     let size = size_from_term(&size)?;
     let index = bounds_checked_index(&memory, size, offset, index)?;
     let view = memory.view::<u8>();
@@ -272,3 +390,162 @@ pub fn write_binary(
     }
     Ok(atoms::ok())
 }
+
+// A memory region being streamed to a process by `stream_read`.
+// The streaming thread waits for the receiver to acknowledge each chunk before reading the next one.
+// The thread only holds the shared `control`, so that the stream is cancelled once the receiver
+// drops its handle, e.g. because it exited without cancelling the stream.
+pub struct MemoryStreamResource {
+    control: Arc<StreamControl>,
+}
+
+impl Drop for MemoryStreamResource {
+    fn drop(&mut self) {
+        self.control.cancel();
+    }
+}
+
+struct StreamControl {
+    continue_signal: Condvar,
+    state: Mutex<StreamState>,
+}
+
+#[derive(Default)]
+struct StreamState {
+    acknowledged: bool,
+    cancelled: bool,
+}
+
+impl StreamControl {
+    // The state is only ever set to whole values, so it is consistent even if a thread panicked while holding the lock.
+    fn lock_state(&self) -> MutexGuard<'_, StreamState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    // blocks until the last chunk was acknowledged, returns false if the stream was cancelled instead
+    fn wait_for_ack(&self) -> bool {
+        let mut state = self.lock_state();
+        while !state.acknowledged && !state.cancelled {
+            state = self
+                .continue_signal
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state.acknowledged = false;
+        !state.cancelled
+    }
+
+    fn acknowledge(&self) {
+        self.lock_state().acknowledged = true;
+        self.continue_signal.notify_one();
+    }
+
+    fn cancel(&self) {
+        self.lock_state().cancelled = true;
+        self.continue_signal.notify_one();
+    }
+}
+
+// Sends the given memory region to the `receiver` pid in chunks of at most `chunk_size` bytes.
+// Chunks are sent as `{:memory_chunk, stream_ref, chunk_index, binary}` messages from a separate thread,
+// followed by a final `{:memory_done, stream_ref}` message (or `{:memory_error, stream_ref, reason}`).
+// The next chunk is only read and sent once the previous one was acknowledged with `stream_ack`,
+// so at most one chunk is in flight.
+// Chunks are not a consistent snapshot: a running guest may write to memory between two chunks.
+#[rustler::nif(name = "memory_stream_read")]
+pub fn stream_read(
+    resource: ResourceArc<MemoryResource>,
+    size: Term,
+    offset: usize,
+    index: usize,
+    len: usize,
+    chunk_size: usize,
+    receiver: (LocalPid, Term),
+) -> NifResult<(Atom, ResourceArc<MemoryStreamResource>)> {
+    let (pid, stream_ref) = receiver;
+    if chunk_size == 0 {
+        return Err(Error::RaiseTerm(Box::new(
+            "Chunk size must be greater than zero.",
+        )));
+    }
+    let start = {
+        let memory = resource.lock()?;
+        let size = size_from_term(&size)?;
+        let index = bounds_checked_index(&memory, size, offset, index)?;
+        if offset + index + len > memory.view::<u8>().len() {
+            return Err(Error::RaiseTerm(Box::new(
+                "Out of bound: The given binary will read out of memory",
+            )));
+        }
+        offset + index
+    };
+    let end = start + len;
+    let control = Arc::new(StreamControl {
+        continue_signal: Condvar::new(),
+        state: Mutex::new(StreamState::default()),
+    });
+    let thread_control = control.clone();
+    let stream_ref = BoxedTerm::new(stream_ref);
+
+    thread::spawn(move || {
+        let control = thread_control;
+        let mut msg_env = OwnedEnv::new();
+        for (chunk_index, chunk_start) in (start..end).step_by(chunk_size).enumerate() {
+            if chunk_index > 0 && !control.wait_for_ack() {
+                return;
+            }
+            let chunk_end = cmp::min(chunk_start + chunk_size, end);
+            let data = match resource.memory.lock() {
+                Ok(memory) => {
+                    // Safety: see `snapshot`
+                    let bytes = unsafe { memory.data_unchecked() };
+                    bytes[chunk_start..chunk_end].to_vec()
+                }
+                Err(_) => {
+                    msg_env.send_and_clear(&pid, |env| {
+                        (
+                            atoms::memory_error(),
+                            stream_ref.load(env),
+                            atoms::memory_poisoned(),
+                        )
+                            .encode(env)
+                    });
+                    return;
+                }
+            };
+            msg_env.send_and_clear(&pid, |env| {
+                let mut binary: OwnedBinary = OwnedBinary::new(data.len()).unwrap();
+                binary.copy_from_slice(&data);
+                (
+                    atoms::memory_chunk(),
+                    stream_ref.load(env),
+                    chunk_index,
+                    binary.release(env),
+                )
+                    .encode(env)
+            });
+        }
+        msg_env.send_and_clear(&pid, |env| {
+            (atoms::memory_done(), stream_ref.load(env)).encode(env)
+        });
+    });
+
+    Ok((
+        atoms::ok(),
+        ResourceArc::new(MemoryStreamResource { control }),
+    ))
+}
+
+// called once the receiver is ready for the next chunk
+#[rustler::nif(name = "memory_stream_ack")]
+pub fn stream_ack(stream: ResourceArc<MemoryStreamResource>) -> Atom {
+    stream.control.acknowledge();
+    atoms::ok()
+}
+
+// stops the stream, no further chunks are sent
+#[rustler::nif(name = "memory_stream_cancel")]
+pub fn stream_cancel(stream: ResourceArc<MemoryStreamResource>) -> Atom {
+    stream.control.cancel();
+    atoms::ok()
+}
@@ -30,7 +30,11 @@ pub fn receive_callback_result(
         vec![]
     };
 
-    let mut result = token_resource.token.return_values.lock().unwrap();
+    let mut result = token_resource
+        .token
+        .return_values
+        .lock()
+        .map_err(|_| Error::Atom("callback token is poisoned"))?;
     *result = Some((success, results));
     token_resource.token.continue_signal.notify_one();
 
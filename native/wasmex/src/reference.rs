@@ -0,0 +1,51 @@
+//! WebAssembly reference values: `funcref` handles returned to Elixir and Elixir terms boxed as `externref`.
+
+use std::sync::{Mutex, PoisonError};
+
+use rustler::{
+    env::{OwnedEnv, SavedTerm},
+    Env, Term,
+};
+use wasmer::{Type, Val};
+
+pub struct RefResource {
+    pub value: Val,
+}
+
+// wasmer does not mark `ExternRef` as Send/Sync because it wraps a raw pointer.
+// Its reference count is atomic and it only ever holds `Send + Sync` data, so sharing it is safe.
+unsafe impl Send for RefResource {}
+unsafe impl Sync for RefResource {}
+
+impl RefResource {
+    pub fn new(value: Val) -> Self {
+        Self { value }
+    }
+
+    pub fn value_type(&self) -> Type {
+        self.value.ty()
+    }
+}
+
+/// An Elixir term passed into WebAssembly as an `externref`.
+pub struct BoxedTerm {
+    term: Mutex<(OwnedEnv, SavedTerm)>,
+}
+
+impl BoxedTerm {
+    pub fn new(term: Term) -> Self {
+        let env = OwnedEnv::new();
+        let saved_term = env.save(term);
+        Self {
+            term: Mutex::new((env, saved_term)),
+        }
+    }
+
+    /// Copies the boxed term into the given environment.
+    pub fn load<'a>(&self, env: Env<'a>) -> Term<'a> {
+        // the saved term is never changed after boxing, so a poisoned lock still guards a valid term
+        let term = self.term.lock().unwrap_or_else(PoisonError::into_inner);
+        let (owned_env, saved_term) = &*term;
+        owned_env.run(|owned_env| saved_term.load(owned_env).in_env(env))
+    }
+}
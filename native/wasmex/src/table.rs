@@ -0,0 +1,92 @@
+//! Table API of an WebAssembly instance.
+
+use rustler::{resource::ResourceArc, Atom, Error, NifResult, Term};
+use wasmer::{Table, Val};
+
+use crate::{
+    atoms,
+    instance::{decode_function_param_terms, encode_value, map_to_wasmer_values, InstanceResource},
+};
+
+#[rustler::nif(name = "instance_table_size")]
+pub fn size(resource: ResourceArc<InstanceResource>, name: String) -> NifResult<(Atom, u32)> {
+    let table = table_from_instance(&resource, &name)?;
+    Ok((atoms::ok(), table.size()))
+}
+
+#[rustler::nif(name = "instance_table_get")]
+pub fn get<'a>(
+    env: rustler::Env<'a>,
+    resource: ResourceArc<InstanceResource>,
+    name: String,
+    index: u32,
+) -> NifResult<(Atom, Term<'a>)> {
+    let table = table_from_instance(&resource, &name)?;
+    let value = table.get(index).ok_or_else(|| {
+        Error::Term(Box::new(format!(
+            "Index {} is out of bounds for table `{}` of size {}.",
+            index,
+            name,
+            table.size()
+        )))
+    })?;
+    Ok((atoms::ok(), encode_value(env, value)))
+}
+
+#[rustler::nif(name = "instance_table_set")]
+pub fn set(
+    resource: ResourceArc<InstanceResource>,
+    name: String,
+    index: u32,
+    value: Term,
+) -> NifResult<Atom> {
+    let table = table_from_instance(&resource, &name)?;
+    let value = decode_element(&table, value)?;
+    table.set(index, value).map_err(|e| {
+        Error::Term(Box::new(format!(
+            "Could not set element {} of table `{}`: {}",
+            index,
+            name,
+            e.message()
+        )))
+    })?;
+    Ok(atoms::ok())
+}
+
+// returns the previous size of the table
+#[rustler::nif(name = "instance_table_grow")]
+pub fn grow(
+    resource: ResourceArc<InstanceResource>,
+    name: String,
+    delta: u32,
+    init: Term,
+) -> NifResult<(Atom, u32)> {
+    let table = table_from_instance(&resource, &name)?;
+    let init = decode_element(&table, init)?;
+    let previous_size = table.grow(delta, init).map_err(|e| {
+        Error::Term(Box::new(format!(
+            "Could not grow table `{}`: {}",
+            name,
+            e.message()
+        )))
+    })?;
+    Ok((atoms::ok(), previous_size))
+}
+
+// the table is cloned, so that the instance is not locked while working with it
+fn table_from_instance(resource: &InstanceResource, name: &str) -> NifResult<Table> {
+    let instance = resource.lock()?;
+    instance.exports.get_table(name).cloned().map_err(|_| {
+        Error::Term(Box::new(format!(
+            "The WebAssembly module has no exported table named `{}`.",
+            name
+        )))
+    })
+}
+
+// elements are decoded like function params, so `nil` is a null reference
+fn decode_element(table: &Table, term: Term) -> NifResult<Val> {
+    let values = decode_function_param_terms(&[table.ty().ty], vec![term])
+        .map_err(|reason| Error::Term(Box::new(reason)))?;
+    Ok(map_to_wasmer_values(&values).remove(0))
+}
@@ -0,0 +1,36 @@
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 16))
+  (global $freed (mut i32) (i32.const 0))
+  (func $alloc (export "alloc") (param $len i32) (result i32)
+    (local $ptr i32)
+    (local.set $ptr (global.get $next))
+    (global.set $next (i32.add (global.get $next) (local.get $len)))
+    (local.get $ptr)
+  )
+  (func (export "dealloc") (param $ptr i32) (param $len i32)
+    (global.set $freed (i32.add (global.get $freed) (i32.const 1)))
+  )
+  (func (export "freed") (result i32) (global.get $freed))
+  (func (export "trap") (param $ptr i32) (param $len i32) (result i32 i32) unreachable)
+  (func (export "reverse") (param $ptr i32) (param $len i32) (result i32 i32)
+    (local $out i32)
+    (local $i i32)
+    (local.set $out (call $alloc (local.get $len)))
+    (block $done
+      (loop $copy
+        (br_if $done (i32.ge_u (local.get $i) (local.get $len)))
+        (i32.store8
+          (i32.add (local.get $out) (local.get $i))
+          (i32.load8_u
+            (i32.sub
+              (i32.add (local.get $ptr) (local.get $len))
+              (i32.add (local.get $i) (i32.const 1)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $copy)
+      )
+    )
+    (local.get $out)
+    (local.get $len)
+  )
+)
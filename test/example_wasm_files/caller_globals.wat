@@ -0,0 +1,9 @@
+(module
+  (import "env" "set_error_code" (func $set_error_code))
+  (global $error_code (export "error_code") (mut i32) (i32.const 0))
+  (global (export "answer") i64 (i64.const 42))
+  (func (export "call_import") (result i32)
+    call $set_error_code
+    global.get $error_code
+  )
+)
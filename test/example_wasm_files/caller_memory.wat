@@ -0,0 +1,8 @@
+(module
+  (import "env" "read_first_byte" (func $read_first_byte (result i32)))
+  (memory (export "linear_memory") 1)
+  (data (i32.const 0) "\2a")
+  (func (export "call_import") (result i32)
+    call $read_first_byte
+  )
+)
@@ -0,0 +1,35 @@
+(module
+  (memory (export "memory") 1)
+  (global $stored (mut externref) (ref.null extern))
+  (func (export "null_extern_ref") (result externref)
+    ref.null extern
+  )
+  (func (export "is_null_extern_ref") (param externref) (result i32)
+    local.get 0
+    ref.is_null
+  )
+  (func (export "store_extern_ref") (param externref)
+    local.get 0
+    global.set $stored
+  )
+  (func (export "stored_extern_ref") (result externref)
+    global.get $stored
+  )
+  (type $returns_i32 (func (result i32)))
+  (table $table (export "table") 1 funcref)
+  (elem declare func $forty_two)
+  (func $forty_two (result i32)
+    i32.const 42
+  )
+  (func (export "func_ref") (result funcref)
+    ref.func $forty_two
+  )
+  (func (export "is_null_func_ref") (param funcref) (result i32)
+    local.get 0
+    ref.is_null
+  )
+  (func (export "call_func_ref") (param funcref) (result i32)
+    (table.set $table (i32.const 0) (local.get 0))
+    (call_indirect $table (type $returns_i32) (i32.const 0))
+  )
+)
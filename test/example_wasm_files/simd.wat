@@ -0,0 +1,8 @@
+(module
+  (import "env" "xor" (func $xor (param v128 v128) (result v128)))
+  (func (export "xor") (param v128 v128) (result v128)
+    local.get 0
+    local.get 1
+    call $xor
+  )
+)
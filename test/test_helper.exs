@@ -1,6 +1,7 @@
 defmodule TestHelper do
   @wasm_test_source_dir "#{Path.dirname(__ENV__.file)}/wasm_test"
   @wasm_import_test_source_dir "#{Path.dirname(__ENV__.file)}/wasm_import_test"
+  @example_wasm_files_dir "#{Path.dirname(__ENV__.file)}/example_wasm_files"
 
   def wasm_test_file_path,
     do: "#{@wasm_test_source_dir}/target/wasm32-unknown-unknown/debug/wasmex_test.wasm"
@@ -8,6 +9,8 @@ defmodule TestHelper do
   def wasm_import_test_file_path,
     do: "#{@wasm_import_test_source_dir}/target/wasm32-unknown-unknown/debug/wasmex_test.wasm"
 
+  def example_wasm_file_path(name), do: "#{@example_wasm_files_dir}/#{name}"
+
   def compile_wasm_files do
     {"", 0} = System.cmd("cargo", ["build"], cd: @wasm_test_source_dir)
     {"", 0} = System.cmd("cargo", ["build"], cd: @wasm_import_test_source_dir)
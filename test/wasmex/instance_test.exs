@@ -83,6 +83,32 @@ defmodule Wasmex.InstanceTest do
     end
   end
 
+  describe "from_file/2" do
+    test "instantiates an Instance from a wasm file" do
+      {:ok, instance} = Wasmex.Instance.from_file(TestHelper.wasm_test_file_path(), %{})
+      assert Wasmex.Instance.function_export_exists(instance, "sum")
+    end
+
+    test "instantiates an Instance from a wat file" do
+      path = TestHelper.example_wasm_file_path("reference_types.wat")
+      {:ok, instance} = Wasmex.Instance.from_file(path, %{})
+      assert Wasmex.Instance.function_export_exists(instance, "func_ref")
+    end
+
+    test "instantiates an Instance with imports" do
+      imports = %{
+        "env" => TestHelper.default_imported_functions_env_stringified()
+      }
+
+      {:ok, _} = Wasmex.Instance.from_file(TestHelper.wasm_import_test_file_path(), imports)
+    end
+
+    test "errors with the file path when the file can not be read" do
+      {:error, reason} = Wasmex.Instance.from_file("does/not/exist.wasm", %{})
+      assert reason =~ "Could not compile module from `does/not/exist.wasm`"
+    end
+  end
+
   describe "function_export_exists/2" do
     test "returns whether a function export could be found in the wasm file" do
       {:ok, instance} = build_wasm_instance()
@@ -92,6 +118,100 @@ defmodule Wasmex.InstanceTest do
     end
   end
 
+  describe "healthy?/1" do
+    test "returns true for a usable instance" do
+      {:ok, instance} = build_wasm_instance()
+      assert Wasmex.Instance.healthy?(instance)
+    end
+  end
+
+  describe "poisoned instances" do
+    setup do
+      {:ok, instance} = build_wasm_instance()
+      :ok = Wasmex.Native.instance_poison_for_testing(instance.resource)
+      %{instance: instance}
+    end
+
+    test "are not healthy", %{instance: instance} do
+      refute Wasmex.Instance.healthy?(instance)
+    end
+
+    test "have no function exports", %{instance: instance} do
+      refute Wasmex.Instance.function_export_exists(instance, "sum")
+    end
+
+    test "return an error instead of crashing", %{instance: instance} do
+      assert {:error, :instance_poisoned} == Wasmex.Instance.exported_functions(instance)
+      assert {:error, :instance_poisoned} == Wasmex.Instance.list_functions(instance)
+      assert {:error, :instance_poisoned} == Wasmex.Instance.resolve_function(instance, "sum")
+      assert {:error, :instance_poisoned} == Wasmex.Instance.memory(instance, :uint8, 0)
+    end
+
+    test "reply with an error to function calls", %{instance: instance} do
+      :ok = Wasmex.Instance.call_exported_function(instance, "sum", [1, 2], :fake_from)
+      assert_receive {:returned_function_call, {:error, :instance_poisoned}, :fake_from}
+    end
+  end
+
+  describe "exported_functions/1" do
+    test "returns the signatures of all exported functions" do
+      {:ok, instance} = build_wasm_instance()
+      functions = Wasmex.Instance.exported_functions(instance)
+
+      assert {[:i32, :i32], [:i32]} == Map.get(functions, "sum")
+      assert {[], [:i32]} == Map.get(functions, "arity_0")
+      assert {[:i32, :i64, :f32, :f64], [:f64]} == Map.get(functions, "i32_i64_f32_f64_f64")
+      assert {[], []} == Map.get(functions, "void")
+      refute Map.has_key?(functions, "memory")
+    end
+  end
+
+  describe "list_functions/1" do
+    test "lists exported functions with a stable index" do
+      {:ok, instance} = build_wasm_instance()
+      functions = Wasmex.Instance.list_functions(instance)
+
+      assert functions == Wasmex.Instance.list_functions(instance)
+      assert Enum.map(functions, fn {_name, index, _signature} -> index end) ==
+               Enum.to_list(0..(length(functions) - 1))
+
+      assert {"sum", _index, {[:i32, :i32], [:i32]}} = List.keyfind(functions, "sum", 0)
+    end
+  end
+
+  describe "call_function_by_index/4" do
+    test "calls the function at the given index" do
+      {:ok, instance} = build_wasm_instance()
+      {"sum", index, _signature} = List.keyfind(Wasmex.Instance.list_functions(instance), "sum", 0)
+
+      for a <- 1..3 do
+        assert :ok == Wasmex.Instance.call_function_by_index(instance, index, [a, 2], :fake_from)
+        expected = a + 2
+
+        receive do
+          {:returned_function_call, {:ok, [^expected]}, :fake_from} -> nil
+        after
+          2000 ->
+            raise "message_expected"
+        end
+      end
+    end
+
+    test "sends an error message back to self for unknown indexes" do
+      {:ok, instance} = build_wasm_instance()
+      assert :ok == Wasmex.Instance.call_function_by_index(instance, 1000, [], :fake_from)
+
+      receive do
+        {:returned_function_call, {:error, "exported function with index 1000 not found"},
+         :fake_from} ->
+          nil
+      after
+        1000 ->
+          raise "message_expected"
+      end
+    end
+  end
+
   describe "call_exported_function/3" do
     test "calling a function sends an async message back to self" do
       {:ok, instance} = build_wasm_instance()
@@ -175,6 +295,92 @@ defmodule Wasmex.InstanceTest do
     end
   end
 
+  describe "resolve_function/2" do
+    test "returns a function handle which can be called repeatedly" do
+      {:ok, instance} = build_wasm_instance()
+      {:ok, %Wasmex.Function{} = function} = Wasmex.Instance.resolve_function(instance, "sum")
+
+      for a <- 1..3 do
+        assert :ok == Wasmex.Function.call(function, [a, 2], :fake_from)
+        expected = a + 2
+
+        receive do
+          {:returned_function_call, {:ok, [^expected]}, :fake_from} -> nil
+        after
+          2000 ->
+            raise "message_expected"
+        end
+      end
+    end
+
+    test "returns an error for unknown functions" do
+      {:ok, instance} = build_wasm_instance()
+
+      assert {:error, "exported function `sum42` not found"} ==
+               Wasmex.Instance.resolve_function(instance, "sum42")
+    end
+
+    test "calling a function handle with wrong params sends an error message back to self" do
+      {:ok, instance} = build_wasm_instance()
+      {:ok, function} = Wasmex.Instance.resolve_function(instance, "arity_0")
+      assert :ok == Wasmex.Function.call(function, [1], :fake_from)
+
+      receive do
+        {:returned_function_call, {:error, "number of params does not match. expected 0, got 1"},
+         :fake_from} ->
+          nil
+      after
+        1000 ->
+          raise "message_expected"
+      end
+    end
+  end
+
+  describe "table_size/2, table_get/3, table_set/4, and table_grow/4" do
+    defp build_reference_types_instance do
+      Wasmex.Instance.from_file(TestHelper.example_wasm_file_path("reference_types.wat"), %{})
+    end
+
+    defp func_ref(instance) do
+      :ok = Wasmex.Instance.call_exported_function(instance, "func_ref", [], :fake_from)
+
+      receive do
+        {:returned_function_call, {:ok, [func_ref]}, :fake_from} -> func_ref
+      after
+        2000 ->
+          raise "message_expected"
+      end
+    end
+
+    test "reads, writes, and grows an exported table" do
+      {:ok, instance} = build_reference_types_instance()
+      assert {:ok, 1} == Wasmex.Instance.table_size(instance, "table")
+      assert {:ok, nil} == Wasmex.Instance.table_get(instance, "table", 0)
+
+      func_ref = func_ref(instance)
+      assert :ok == Wasmex.Instance.table_set(instance, "table", 0, func_ref)
+      assert {:ok, element} = Wasmex.Instance.table_get(instance, "table", 0)
+      assert is_reference(element)
+
+      assert {:ok, 1} == Wasmex.Instance.table_grow(instance, "table", 2, nil)
+      assert {:ok, 3} == Wasmex.Instance.table_size(instance, "table")
+      assert {:ok, nil} == Wasmex.Instance.table_get(instance, "table", 2)
+    end
+
+    test "returns errors for unknown tables, out of bounds indexes, and wrong elements" do
+      {:ok, instance} = build_reference_types_instance()
+
+      assert {:error, "The WebAssembly module has no exported table named `missing`."} ==
+               Wasmex.Instance.table_size(instance, "missing")
+
+      assert {:error, "Index 1 is out of bounds for table `table` of size 1."} ==
+               Wasmex.Instance.table_get(instance, "table", 1)
+
+      assert {:error, "Cannot convert argument #1 to a WebAssembly funcref value. Given `42`."} ==
+               Wasmex.Instance.table_set(instance, "table", 0, 42)
+    end
+  end
+
   describe "memory/3" do
     test "returns a memory struct" do
       {:ok, instance} = build_wasm_instance()
@@ -51,6 +51,26 @@ defmodule Wasmex.MemoryTest do
       {:ok, memory} = build_memory(:int32, 0)
       assert Wasmex.Memory.bytes_per_element(memory) == 4
     end
+
+    test "returns number of bytes for uint64" do
+      {:ok, memory} = build_memory(:uint64, 0)
+      assert Wasmex.Memory.bytes_per_element(memory) == 8
+    end
+
+    test "returns number of bytes for int64" do
+      {:ok, memory} = build_memory(:int64, 0)
+      assert Wasmex.Memory.bytes_per_element(memory) == 8
+    end
+
+    test "returns number of bytes for float32" do
+      {:ok, memory} = build_memory(:float32, 0)
+      assert Wasmex.Memory.bytes_per_element(memory) == 4
+    end
+
+    test "returns number of bytes for float64" do
+      {:ok, memory} = build_memory(:float64, 0)
+      assert Wasmex.Memory.bytes_per_element(memory) == 8
+    end
   end
 
   # in bytes
@@ -74,6 +94,11 @@ defmodule Wasmex.MemoryTest do
       {:ok, memory} = build_memory(:int32, 0)
       assert Wasmex.Memory.length(memory) == @min_memory_size / 4
     end
+
+    test "returns number of uint64 elements that fit into memory" do
+      {:ok, memory} = build_memory(:uint64, 0)
+      assert Wasmex.Memory.length(memory) == @min_memory_size / 8
+    end
   end
 
   describe "grow/2" do
@@ -87,6 +112,45 @@ defmodule Wasmex.MemoryTest do
     end
   end
 
+  describe "snapshot/1 and restore/2" do
+    test "restores the bytes of the snapshot" do
+      {:ok, memory} = build_memory(:uint8, 0)
+      :ok = Wasmex.Memory.write_binary(memory, 0, "hello")
+      snapshot = Wasmex.Memory.snapshot(memory)
+
+      :ok = Wasmex.Memory.write_binary(memory, 0, "world")
+      :ok = Wasmex.Memory.set(memory, @min_memory_size - 1, 42)
+      assert :ok == Wasmex.Memory.restore(memory, snapshot)
+
+      assert Wasmex.Memory.read_binary(memory, 0, 5) == "hello"
+      assert Wasmex.Memory.get(memory, @min_memory_size - 1) == 0
+    end
+
+    test "zeroes memory grown after the snapshot" do
+      {:ok, memory} = build_memory(:uint8, 0)
+      snapshot = Wasmex.Memory.snapshot(memory)
+
+      Wasmex.Memory.grow(memory, 1)
+      :ok = Wasmex.Memory.set(memory, @min_memory_size, 42)
+      assert :ok == Wasmex.Memory.restore(memory, snapshot)
+
+      assert Wasmex.Memory.length(memory) == @min_memory_size + @page_size
+      assert Wasmex.Memory.get(memory, @min_memory_size) == 0
+    end
+
+    test "grows memory smaller than the snapshot" do
+      {:ok, large_memory} = build_memory(:uint8, 0)
+      Wasmex.Memory.grow(large_memory, 2)
+      :ok = Wasmex.Memory.set(large_memory, @min_memory_size + @page_size, 42)
+      snapshot = Wasmex.Memory.snapshot(large_memory)
+
+      {:ok, memory} = build_memory(:uint8, 0)
+      assert :ok == Wasmex.Memory.restore(memory, snapshot)
+      assert Wasmex.Memory.length(memory) == @min_memory_size + 2 * @page_size
+      assert Wasmex.Memory.get(memory, @min_memory_size + @page_size) == 42
+    end
+  end
+
   describe "get/2 and set/3" do
     test "sets and gets uint8 values" do
       {:ok, memory} = build_memory(:uint8, 0)
@@ -94,6 +158,44 @@ defmodule Wasmex.MemoryTest do
       :ok = Wasmex.Memory.set(memory, 0, 42)
       assert Wasmex.Memory.get(memory, 0) == 42
     end
+
+    test "sets and gets 64-bit integer values" do
+      {:ok, memory} = build_memory(:uint64, 0)
+      :ok = Wasmex.Memory.set(memory, 1, 18_446_744_073_709_551_615)
+      assert Wasmex.Memory.get(memory, 1) == 18_446_744_073_709_551_615
+      assert Wasmex.Memory.get(memory, :int64, 0, 1) == -1
+      # the second element starts at byte 8
+      assert Wasmex.Memory.get(memory, :uint8, 0, 8) == 255
+    end
+
+    test "sets and gets float values" do
+      {:ok, memory} = build_memory(:float64, 0)
+      :ok = Wasmex.Memory.set(memory, 0, 3.5)
+      assert Wasmex.Memory.get(memory, 0) == 3.5
+
+      :ok = Wasmex.Memory.set(memory, :float32, 0, 0, 1.5)
+      assert Wasmex.Memory.get(memory, :float32, 0, 0) == 1.5
+    end
+
+    test "sets and gets NaN and infinite float values as atoms" do
+      {:ok, memory} = build_memory(:float64, 0)
+
+      for value <- [:nan, :infinity, :neg_infinity] do
+        :ok = Wasmex.Memory.set(memory, 0, value)
+        assert Wasmex.Memory.get(memory, 0) == value
+
+        :ok = Wasmex.Memory.set(memory, :float32, 0, 0, value)
+        assert Wasmex.Memory.get(memory, :float32, 0, 0) == value
+      end
+    end
+
+    test "raises when a float is too large for float32" do
+      {:ok, memory} = build_memory(:float32, 0)
+
+      assert_raise ErlangError, fn ->
+        Wasmex.Memory.set(memory, 0, 1.0e300)
+      end
+    end
   end
 
   describe "write_binary/3" do
@@ -150,6 +252,68 @@ defmodule Wasmex.MemoryTest do
     end
   end
 
+  describe "stream_read/5" do
+    defp receive_chunks(stream, expected_index, acc) do
+      reference = stream.reference
+
+      receive do
+        {:memory_chunk, ^reference, ^expected_index, chunk} ->
+          :ok = Wasmex.Memory.ack_chunk(stream)
+          receive_chunks(stream, expected_index + 1, [chunk | acc])
+
+        {:memory_done, ^reference} ->
+          acc |> Enum.reverse() |> IO.iodata_to_binary()
+      after
+        2000 -> raise "message_expected"
+      end
+    end
+
+    test "streams a large memory region in chunks" do
+      {:ok, memory} = build_memory(:uint8, 0)
+      length = 16 * 1024 * 1024
+      chunk_size = 1024 * 1024
+      Wasmex.Memory.grow(memory, div(length, @page_size))
+      bytes = :crypto.strong_rand_bytes(length)
+      :ok = Wasmex.Memory.write_binary(memory, 0, bytes)
+
+      {:ok, stream} = Wasmex.Memory.stream_read(memory, 0, length, chunk_size, self())
+      assert receive_chunks(stream, 0, []) == bytes
+    end
+
+    test "sends a last, shorter chunk" do
+      {:ok, memory} = build_memory(:uint8, 0)
+      :ok = Wasmex.Memory.write_binary(memory, 0, "hello")
+
+      {:ok, %{reference: reference} = stream} = Wasmex.Memory.stream_read(memory, 0, 5, 2, self())
+      assert_receive {:memory_chunk, ^reference, 0, "he"}
+      :ok = Wasmex.Memory.ack_chunk(stream)
+      assert_receive {:memory_chunk, ^reference, 1, "ll"}
+      :ok = Wasmex.Memory.ack_chunk(stream)
+      assert_receive {:memory_chunk, ^reference, 2, "o"}
+      assert_receive {:memory_done, ^reference}
+    end
+
+    test "waits for the receiver to acknowledge a chunk" do
+      {:ok, memory} = build_memory(:uint8, 0)
+      :ok = Wasmex.Memory.write_binary(memory, 0, "hello")
+
+      {:ok, %{reference: reference} = stream} = Wasmex.Memory.stream_read(memory, 0, 5, 2, self())
+      assert_receive {:memory_chunk, ^reference, 0, "he"}
+      refute_receive {:memory_chunk, ^reference, 1, _}, 100
+
+      :ok = Wasmex.Memory.cancel_stream(stream)
+      refute_receive {:memory_done, ^reference}, 100
+    end
+
+    test "raises when reading out of memory" do
+      {:ok, memory} = build_memory(:uint8, 0)
+
+      assert_raise ErlangError, fn ->
+        Wasmex.Memory.stream_read(memory, 0, @min_memory_size + 1, 1024, self())
+      end
+    end
+  end
+
   describe "read_string/3" do
     test "reads a string from memory" do
       {:ok, memory} = build_memory(:uint8, 0)
@@ -4,6 +4,7 @@ defmodule WasmexTest do
 
   @bytes File.read!(TestHelper.wasm_test_file_path())
   @import_test_bytes File.read!(TestHelper.wasm_import_test_file_path())
+  @reference_types_bytes File.read!(TestHelper.example_wasm_file_path("reference_types.wat"))
 
   defp create_instance(_context) do
     instance = start_supervised!({Wasmex, @bytes})
@@ -21,6 +22,37 @@ defmodule WasmexTest do
       assert !Wasmex.function_exists(instance, "unknown_function")
     end
 
+    test "healthy?", %{instance: instance} do
+      assert Wasmex.healthy?(instance)
+    end
+
+    test "exported_functions", %{instance: instance} do
+      assert {[:f32], [:f32]} == Map.get(Wasmex.exported_functions(instance), "f32_f32")
+    end
+
+    test "call_function_by_index", %{instance: instance} do
+      {"sum", index, _signature} = List.keyfind(Wasmex.list_functions(instance), "sum", 0)
+      assert {:ok, [42]} == Wasmex.call_function_by_index(instance, index, [50, -8])
+    end
+
+    test "call_resolved_function", %{instance: instance} do
+      {:ok, function} = Wasmex.resolve_function(instance, :sum)
+      assert {:ok, [42]} == Wasmex.call_resolved_function(instance, function, [50, -8])
+      assert {:ok, [3]} == Wasmex.call_resolved_function(instance, function, [1, 2])
+    end
+
+    test "call_resolved_function: function of another instance", %{instance: instance} do
+      other_instance =
+        start_supervised!(
+          Supervisor.child_spec({Wasmex, %{bytes: @bytes, imports: %{}}}, id: :other_instance)
+        )
+
+      {:ok, function} = Wasmex.resolve_function(other_instance, :sum)
+
+      assert {:error, "the function was resolved from another instance"} ==
+               Wasmex.call_resolved_function(instance, function, [50, -8])
+    end
+
     test "call_function: calling an unknown function", %{instance: instance} do
       assert {:error, "exported function `unknown_function` not found"} =
                Wasmex.call_function(instance, :unknown_function, [1])
@@ -45,7 +77,8 @@ defmodule WasmexTest do
 
       # giving a value greater than i32::max_value()
       # see: https://doc.rust-lang.org/std/primitive.i32.html#method.max_value
-      assert {:error, "Cannot convert argument #1 to a WebAssembly i32 value."} ==
+      assert {:error,
+              "Cannot convert argument #1 to a WebAssembly i32 value. Given `3000000000`."} ==
                Wasmex.call_function(instance, :i32_i32, [3_000_000_000])
     end
 
@@ -65,7 +98,7 @@ defmodule WasmexTest do
                       0.001
 
       # a value greater than f32::max_value()
-      assert {:error, "Cannot convert argument #1 to a WebAssembly f32 value."} ==
+      assert {:error, "Cannot convert argument #1 to a WebAssembly f32 value. Given `" <> _} =
                Wasmex.call_function(instance, :f32_f32, [3.5e38])
     end
 
@@ -80,6 +113,16 @@ defmodule WasmexTest do
       assert {:ok, [3.5e38]} == Wasmex.call_function(instance, :f64_f64, [3.5e38])
     end
 
+    test "call_function: NaN and infinite floats are passed as atoms", %{instance: instance} do
+      for value <- [:nan, :infinity, :neg_infinity] do
+        assert {:ok, [value]} == Wasmex.call_function(instance, :f32_f32, [value])
+        assert {:ok, [value]} == Wasmex.call_function(instance, :f64_f64, [value])
+      end
+
+      assert {:error, "Cannot convert argument #1 to a WebAssembly f64 value. Given `infinite`."} ==
+               Wasmex.call_function(instance, :f64_f64, [:infinite])
+    end
+
     test "call_function: i32_i64_f32_f64_f64(i32, i64, f32, f64) -> f64 function", %{
       instance: instance
     } do
@@ -98,6 +141,23 @@ defmodule WasmexTest do
       assert {:ok, []} == Wasmex.call_function(instance, :void, [])
     end
 
+    test "call_function: void() -> () function with void_as: :ok" do
+      instance =
+        start_supervised!(
+          Supervisor.child_spec({Wasmex, %{bytes: @bytes, imports: %{}, void_as: :ok}},
+            id: :void_as_ok
+          )
+        )
+
+      assert :ok == Wasmex.call_function(instance, :void, [])
+      assert {:ok, [42]} == Wasmex.call_function(instance, :sum, [50, -8])
+    end
+
+    test "start_link: errors on an unknown void_as option" do
+      assert {:error, reason} = Wasmex.start_link(%{bytes: @bytes, imports: %{}, void_as: nil})
+      assert reason == "`void_as` must be `:empty_list` or `:ok`, got: nil"
+    end
+
     test "call_function: string() -> string function", %{instance: instance} do
       {:ok, [pointer]} = Wasmex.call_function(instance, :string, [])
       {:ok, memory} = Wasmex.memory(instance, :uint8, 0)
@@ -141,6 +201,106 @@ defmodule WasmexTest do
     assert 23 == Wasmex.Memory.get(memory, :uint8, 0, 0)
   end
 
+  test "read memory by name from the caller in a callback" do
+    bytes = File.read!(TestHelper.example_wasm_file_path("caller_memory.wat"))
+
+    imports = %{
+      env: %{
+        read_first_byte:
+          {:fn, [], [:i32],
+           fn context ->
+             refute Map.has_key?(context, :memory)
+
+             assert {:error, "The WebAssembly module has no exported memory named `memory`."} ==
+                      Wasmex.Memory.from_caller(context.caller, "memory")
+
+             {:ok, memory} = Wasmex.Memory.from_caller(context.caller, "linear_memory")
+             Wasmex.Memory.get(memory, 0)
+           end}
+      }
+    }
+
+    instance = start_supervised!({Wasmex, %{bytes: bytes, imports: imports}})
+    assert {:ok, [42]} == Wasmex.call_function(instance, :call_import, [])
+  end
+
+  test "read and write globals from the caller in a callback" do
+    bytes = File.read!(TestHelper.example_wasm_file_path("caller_globals.wat"))
+
+    imports = %{
+      env: %{
+        set_error_code:
+          {:fn, [], [],
+           fn context ->
+             assert {:ok, 42} == Wasmex.Caller.get_global(context.caller, "answer")
+
+             assert {:error, "The WebAssembly module has no exported global named `missing`."} ==
+                      Wasmex.Caller.get_global(context.caller, "missing")
+
+             assert {:error, _} = Wasmex.Caller.set_global(context.caller, "answer", 23)
+
+             assert {:error, "Cannot convert the given value to the type of global `error_code`."} ==
+                      Wasmex.Caller.set_global(context.caller, "error_code", 1.5)
+
+             :ok = Wasmex.Caller.set_global(context.caller, "error_code", 7)
+             nil
+           end}
+      }
+    }
+
+    instance = start_supervised!({Wasmex, %{bytes: bytes, imports: imports}})
+    assert {:ok, [7]} == Wasmex.call_function(instance, :call_import, [])
+  end
+
+  test "pass v128 values to and from a callback" do
+    bytes = File.read!(TestHelper.example_wasm_file_path("simd.wat"))
+
+    imports = %{
+      env: %{
+        xor: {:fn, [:v128, :v128], [:v128], fn _context, a, b -> :crypto.exor(a, b) end}
+      }
+    }
+
+    instance = start_supervised!({Wasmex, %{bytes: bytes, imports: imports}})
+    a = :binary.copy(<<0b1010>>, 16)
+    b = :binary.copy(<<0b0110>>, 16)
+    assert {:ok, [:binary.copy(<<0b1100>>, 16)]} == Wasmex.call_function(instance, :xor, [a, b])
+
+    assert {:error, "Cannot convert argument #1 to a WebAssembly v128 value. Given `" <> _} =
+             Wasmex.call_function(instance, :xor, [<<1, 2, 3>>, b])
+  end
+
+  describe "call_function_with_memory/4" do
+    setup do
+      bytes = File.read!(TestHelper.example_wasm_file_path("call_with_memory.wat"))
+      %{instance: start_supervised!({Wasmex, bytes})}
+    end
+
+    test "passes binaries through memory", %{instance: instance} do
+      assert {:ok, "olleh"} ==
+               Wasmex.call_function_with_memory(instance, :reverse, "hello", alloc: "alloc")
+
+      assert {:ok, ""} == Wasmex.call_function_with_memory(instance, :reverse, "", alloc: "alloc")
+    end
+
+    test "returns an error for a missing allocator", %{instance: instance} do
+      assert {:error, "exported function `cabi_realloc` not found"} ==
+               Wasmex.call_function_with_memory(instance, :reverse, "hello")
+    end
+
+    test "returns an error for functions not returning a (ptr, len) pair", %{instance: instance} do
+      assert {:error, "exported function `dealloc` must return a `(ptr, len)` pair of i32 values"} ==
+               Wasmex.call_function_with_memory(instance, :dealloc, "hello", alloc: "alloc")
+    end
+
+    test "frees the input when the call fails", %{instance: instance} do
+      assert {:error, "Error during function excecution: " <> _} =
+               Wasmex.call_function_with_memory(instance, :trap, "hello", alloc: "alloc")
+
+      assert {:ok, [1]} == Wasmex.call_function(instance, :freed, [])
+    end
+  end
+
   describe "when instantiating with imports" do
     def create_instance_with_atom_imports(_context) do
       imports = %{
@@ -217,4 +377,62 @@ defmodule WasmexTest do
                "Error during function excecution: `RuntimeError: the elixir callback threw an exception`."
     end
   end
+
+  describe "when instantiating a module using reference types" do
+    def create_reference_types_instance(_context) do
+      instance = start_supervised!({Wasmex, @reference_types_bytes})
+      %{instance: instance}
+    end
+
+    setup [:create_reference_types_instance]
+
+    test "call_function: a null externref is returned as nil", %{instance: instance} do
+      assert {:ok, [nil]} == Wasmex.call_function(instance, :null_extern_ref, [])
+    end
+
+    test "call_function: nil is passed as a null externref", %{instance: instance} do
+      assert {:ok, [1]} == Wasmex.call_function(instance, :is_null_extern_ref, [nil])
+    end
+
+    test "call_function: a returned null externref can be passed back in", %{instance: instance} do
+      {:ok, [reference]} = Wasmex.call_function(instance, :stored_extern_ref, [])
+      assert {:ok, []} == Wasmex.call_function(instance, :store_extern_ref, [reference])
+      assert {:ok, [^reference]} = Wasmex.call_function(instance, :stored_extern_ref, [])
+    end
+
+    test "call_function: elixir terms are passed as externref", %{instance: instance} do
+      assert {:ok, [0]} == Wasmex.call_function(instance, :is_null_extern_ref, [42])
+    end
+
+    test "call_function: elixir terms round-trip through externref", %{instance: instance} do
+      term = %{pid: self(), list: [1, 2.5, "three"], tuple: {:ok, make_ref()}}
+      assert {:ok, []} == Wasmex.call_function(instance, :store_extern_ref, [term])
+      assert {:ok, [^term]} = Wasmex.call_function(instance, :stored_extern_ref, [])
+      assert {:ok, [^term]} = Wasmex.call_function(instance, :stored_extern_ref, [])
+    end
+
+    test "call_function: externref params reject funcref handles", %{instance: instance} do
+      {:ok, [func_ref]} = Wasmex.call_function(instance, :func_ref, [])
+
+      assert {:error, "Cannot convert argument #1 to a WebAssembly externref value. Given `" <> _} =
+               Wasmex.call_function(instance, :is_null_extern_ref, [func_ref])
+    end
+
+    test "call_function: a funcref is returned as an opaque handle", %{instance: instance} do
+      {:ok, [func_ref]} = Wasmex.call_function(instance, :func_ref, [])
+      assert is_reference(func_ref)
+      assert {:ok, [0]} == Wasmex.call_function(instance, :is_null_func_ref, [func_ref])
+      assert {:ok, [1]} == Wasmex.call_function(instance, :is_null_func_ref, [nil])
+    end
+
+    test "call_function: a returned funcref can be called by the module", %{instance: instance} do
+      {:ok, [func_ref]} = Wasmex.call_function(instance, :func_ref, [])
+      assert {:ok, [42]} == Wasmex.call_function(instance, :call_func_ref, [func_ref])
+    end
+
+    test "call_function: funcref params reject other values", %{instance: instance} do
+      assert {:error, "Cannot convert argument #1 to a WebAssembly funcref value. Given `42`."} ==
+               Wasmex.call_function(instance, :is_null_func_ref, [42])
+    end
+  end
 end